@@ -0,0 +1,74 @@
+use aoc_shared::{fold_decimal_from, ints, Token, Tokenize};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::hint::black_box;
+
+/// Comma separated numbers with `digits` digits, every third negative.
+fn numbers(count: usize, digits: u32) -> Vec<u8> {
+    let modulus = 10i64.pow(digits);
+    (0..count as i64)
+        .map(|i| {
+            let num = (i * 7_919_393_579).rem_euclid(modulus).max(modulus / 10);
+            if i % 3 == 0 {
+                -num
+            } else {
+                num
+            }
+        })
+        .map(|num| num.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+        .into_bytes()
+}
+
+/// How `intcode::read_intcode` did it.
+fn split_parse(input: &[u8]) -> Vec<i64> {
+    std::str::from_utf8(input)
+        .unwrap()
+        .split(|c: char| !c.is_ascii_digit() && c != '-')
+        .filter(|s| !s.is_empty())
+        .map(|num| num.parse::<i64>().unwrap())
+        .collect()
+}
+
+/// Tokenize, then `fold_decimal_from` each token.
+fn tokens_fold(input: &[u8]) -> Vec<i64> {
+    let mut neg = false;
+    let mut ret = vec![];
+    for token in input.tokenize() {
+        match token {
+            Token::Delimiter(b'-') => neg = true,
+            Token::Something(digits) => {
+                let num = fold_decimal_from::<i64>(digits);
+                ret.push(if neg { -num } else { num });
+                neg = false;
+            }
+            _ => neg = false,
+        }
+    }
+    ret
+}
+
+fn bench_ints(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ints");
+    for digits in [3, 9, 18] {
+        let input = numbers(10_000, digits);
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        group.bench_with_input(BenchmarkId::new("ints", digits), &input, |b, input| {
+            b.iter(|| ints::<i64>(black_box(input)).collect::<Vec<_>>())
+        });
+        group.bench_with_input(
+            BenchmarkId::new("split_parse", digits),
+            &input,
+            |b, input| b.iter(|| split_parse(black_box(input))),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("tokens_fold", digits),
+            &input,
+            |b, input| b.iter(|| tokens_fold(black_box(input))),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_ints);
+criterion_main!(benches);
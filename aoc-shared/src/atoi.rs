@@ -1,27 +1,31 @@
-use std::ops::{Add, Mul};
+use std::{
+    fmt,
+    ops::{Add, Mul},
+};
 
-use num::{traits::AsPrimitive, CheckedAdd, CheckedMul};
+use num::{CheckedAdd, CheckedMul, CheckedSub, FromPrimitive, Zero};
+
+/// `n` as a `T`; only used for digits and radixes, which fit in every integer type.
+fn small<T: FromPrimitive>(n: u8) -> T {
+    T::from_u8(n).expect("digit or radix does not fit in T.")
+}
 
 /// Intended to be used with: .iter().fold(num, fold_decimal)
+/// Every byte must be a digit and the result must fit, see `parse_decimal` for a checked version.
 pub fn fold_decimal<T>(acc: T, chr: &u8) -> T
 where
-    T: Copy + 'static,
-    T: Add<Output = T>,
-    T: Mul<Output = T>,
-    u8: num::traits::AsPrimitive<T>,
+    T: Add<Output = T> + Mul<Output = T> + FromPrimitive,
 {
-    acc * 10.as_() + (chr - b'0').as_()
+    acc * small(10) + small(chr - b'0')
 }
 
 /// Intended to be used with: .iter().fold(num, fold_decimal)
+/// Also works for big integers, like `num::BigUint`.
 pub fn fold_decimal_from<T>(number: &[u8]) -> T
 where
-    T: Copy + 'static,
-    T: Add<Output = T>,
-    T: Mul<Output = T>,
-    u8: num::traits::AsPrimitive<T>,
+    T: Zero + Add<Output = T> + Mul<Output = T> + FromPrimitive,
 {
-    number.iter().fold(0.as_(), fold_decimal)
+    number.iter().fold(T::zero(), fold_decimal)
 }
 
 /// Function to convert a byte array into a integer of type T using a base of 2 through 36.
@@ -29,22 +33,15 @@ where
 /// function ignores junk.
 pub fn atoi<T, const RADIX: u8>(number: &[u8]) -> T
 where
-    T: Copy + 'static,
-    T: Add<Output = T>,
-    T: Mul<Output = T>,
-    u8: num::traits::AsPrimitive<T>,
+    T: Zero + Add<Output = T> + Mul<Output = T> + FromPrimitive,
 {
     assert!(RADIX > 1 && RADIX < 37);
-    number.iter().fold(0.as_(), |acc, &chr| {
-        let val = (match chr {
-            b'0'..=b'9' => chr,
-            b'A'..=b'Z' => chr - 7,  // b'9' to b'A' has 7 other chars between
-            b'a'..=b'z' => chr - 39, // b'9' to b'a' has 39 other chars between it.
-            _ => return acc,         // junk.
-        } - b'0');
-        // e.g. if radix is 2, b'3' would cause an early return so to ignore the value.
-        let val = if RADIX <= val { return acc } else { val };
-        acc * RADIX.as_() + val.as_()
+    number.iter().fold(T::zero(), |acc, &chr| {
+        // e.g. if radix is 2, b'3' is junk too.
+        match digit_value(chr, RADIX) {
+            Some(val) => acc * small(RADIX) + small(val),
+            None => acc,
+        }
     })
 }
 
@@ -53,27 +50,117 @@ where
 /// function returns None on failure.
 pub fn try_atoi<T, const RADIX: u8>(number: &[u8]) -> Option<T>
 where
-    T: Copy + 'static,
-    T: CheckedAdd<Output = T>,
-    T: CheckedMul<Output = T>,
-    u8: num::traits::AsPrimitive<T>,
+    T: Zero + CheckedAdd + CheckedMul + FromPrimitive,
 {
     assert!(RADIX > 1 && RADIX < 37);
-    number.iter().try_fold(0.as_(), |acc, &chr| {
-        let val = (match chr {
-            b'0'..=b'9' => chr,
-            b'A'..=b'Z' => chr - 7,  // b'9' to b'A' has 7 other chars between
-            b'a'..=b'z' => chr - 39, // b'9' to b'a' has 39 other chars between it.
-            _ => 255,                // junk.
-        } - b'0');
-        // e.g. if radix is 2, set to 0 if the value b'3' is found.
-        if RADIX <= val {
-            return None;
-        }
-        RADIX.as_().checked_mul(&acc)?.checked_add(&val.as_())
+    number.iter().try_fold(T::zero(), |acc, &chr| {
+        let val = digit_value(chr, RADIX)?;
+        acc.checked_mul(&small(RADIX))?.checked_add(&small(val))
     })
 }
 
+/// Why `parse_int` failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AtoiError {
+    /// No digits, possibly just a sign.
+    Empty,
+    /// `byte` at `offset` is not a digit of the radix.
+    InvalidByte { offset: usize, byte: u8 },
+    /// Too large, or too small, for the type.
+    Overflow,
+}
+
+impl fmt::Display for AtoiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AtoiError::Empty => write!(f, "no digits to parse"),
+            AtoiError::InvalidByte { offset, byte } => {
+                write!(f, "invalid digit {:?} at offset {offset}", *byte as char)
+            }
+            AtoiError::Overflow => write!(f, "number does not fit in the type"),
+        }
+    }
+}
+
+impl std::error::Error for AtoiError {}
+
+/// Value of `chr` as a digit of `radix`, if it is one.
+fn digit_value(chr: u8, radix: u8) -> Option<u8> {
+    let val = match chr {
+        b'0'..=b'9' => chr - b'0',
+        b'A'..=b'Z' => chr - b'A' + 10,
+        b'a'..=b'z' => chr - b'a' + 10,
+        _ => return None,
+    };
+    (val < radix).then_some(val)
+}
+
+/// Checked fold_decimal, intended to be used with: .iter().try_fold(num, checked_fold_decimal)
+pub fn checked_fold_decimal<T>(acc: T, chr: &u8) -> Option<T>
+where
+    T: CheckedMul + CheckedAdd + FromPrimitive,
+{
+    let digit = T::from_u8(digit_value(*chr, 10)?)?;
+    acc.checked_mul(&T::from_u8(10)?)?.checked_add(&digit)
+}
+
+/// Parse an integer of RADIX 2 through 36 with an optional leading `+` or `-`.
+/// Works for any primitive integer, including u128 and i128, as well as big integers.
+/// Negative numbers are accumulated downwards, so `T::MIN` parses; for unsigned types only `-0` does.
+///
+/// # Examples
+///
+/// ```
+/// use aoc_shared::{parse_decimal, parse_int, AtoiError};
+///
+/// assert_eq!(parse_decimal::<i8>(b"-128"), Ok(-128));
+/// assert_eq!(parse_int::<u32, 16>(b"+fF"), Ok(255));
+/// assert_eq!(parse_decimal::<u8>(b"256"), Err(AtoiError::Overflow));
+/// assert_eq!(parse_decimal::<u8>(b"-1"), Err(AtoiError::Overflow));
+/// assert_eq!(parse_decimal::<i32>(b"-"), Err(AtoiError::Empty));
+/// assert_eq!(parse_decimal::<i32>(b"12x"), Err(AtoiError::InvalidByte { offset: 2, byte: b'x' }));
+/// ```
+pub fn parse_int<T, const RADIX: u8>(number: &[u8]) -> Result<T, AtoiError>
+where
+    T: Zero + CheckedMul + CheckedAdd + CheckedSub + FromPrimitive,
+{
+    assert!(RADIX > 1 && RADIX < 37);
+    let (neg, start) = match number.first() {
+        Some(b'-') => (true, 1),
+        Some(b'+') => (false, 1),
+        _ => (false, 0),
+    };
+    if number.len() == start {
+        return Err(AtoiError::Empty);
+    }
+    let radix = T::from_u8(RADIX).ok_or(AtoiError::Overflow)?;
+    number[start..]
+        .iter()
+        .enumerate()
+        .try_fold(T::zero(), |acc, (i, &chr)| {
+            let digit = digit_value(chr, RADIX).ok_or(AtoiError::InvalidByte {
+                offset: start + i,
+                byte: chr,
+            })?;
+            let digit = T::from_u8(digit).ok_or(AtoiError::Overflow)?;
+            let acc = acc.checked_mul(&radix);
+            if neg {
+                acc.and_then(|acc| acc.checked_sub(&digit))
+            } else {
+                acc.and_then(|acc| acc.checked_add(&digit))
+            }
+            .ok_or(AtoiError::Overflow)
+        })
+}
+
+/// `parse_int` in base 10.
+pub fn parse_decimal<T>(number: &[u8]) -> Result<T, AtoiError>
+where
+    T: Zero + CheckedMul + CheckedAdd + CheckedSub + FromPrimitive,
+{
+    parse_int::<T, 10>(number)
+}
+
 /// twenty six (a-z or A-Z) radix number. no 0-9 digits, thus not for atoi.
 /// made const since this is a very special number format for weird number problems.
 pub const fn ts_to_u64(number: &[u8]) -> u64 {
@@ -112,7 +199,12 @@ pub const fn u64_to_ts<const N: usize>(number: u64) -> [u8; N] {
 
 #[cfg(test)]
 mod test {
-    use crate::{ts_to_u64, u64_to_ts};
+    use crate::{
+        atoi, checked_fold_decimal, fold_decimal_from, parse_decimal, parse_int, try_atoi,
+        ts_to_u64, u64_to_ts, AtoiError,
+    };
+    use num::{BigInt, BigUint};
+    use proptest::prelude::*;
 
     #[test]
     fn ts_to_u64_is_u64_to_ts() {
@@ -120,4 +212,61 @@ mod test {
         let nprime = b"tsrcxpeh";
         assert_eq!(&u64_to_ts(n), nprime);
     }
+
+    #[test]
+    fn parse_wide() {
+        assert_eq!(
+            parse_decimal::<i128>(i128::MIN.to_string().as_bytes()),
+            Ok(i128::MIN)
+        );
+        assert_eq!(
+            parse_decimal::<u128>(u128::MAX.to_string().as_bytes()),
+            Ok(u128::MAX)
+        );
+        assert_eq!(
+            parse_decimal::<u128>(b"340282366920938463463374607431768211456"),
+            Err(AtoiError::Overflow)
+        );
+        let big = b"-123456789012345678901234567890123456789012345678901234567890";
+        assert_eq!(
+            parse_decimal::<BigInt>(big),
+            Ok(std::str::from_utf8(big).unwrap().parse::<BigInt>().unwrap())
+        );
+        assert_eq!(
+            parse_int::<u8, 2>(b"102"),
+            Err(AtoiError::InvalidByte {
+                offset: 2,
+                byte: b'2'
+            })
+        );
+        assert_eq!(parse_decimal::<u32>(b""), Err(AtoiError::Empty));
+        assert_eq!(parse_decimal::<u32>(b"-0"), Ok(0));
+        assert_eq!(b"255".iter().try_fold(0u8, checked_fold_decimal), Some(255));
+        assert_eq!(b"256".iter().try_fold(0u8, checked_fold_decimal), None);
+        assert_eq!(b"2-5".iter().try_fold(0u8, checked_fold_decimal), None);
+    }
+
+    #[test]
+    fn unchecked_big() {
+        let digits = b"123456789012345678901234567890123456789012345678901234567890";
+        let expected = std::str::from_utf8(digits)
+            .unwrap()
+            .parse::<BigUint>()
+            .unwrap();
+        assert_eq!(fold_decimal_from::<BigUint>(digits), expected);
+        assert_eq!(atoi::<BigUint, 10>(digits), expected);
+        assert_eq!(try_atoi::<BigUint, 10>(digits), Some(expected));
+        assert_eq!(atoi::<BigUint, 16>(b"ff_ff"), BigUint::from(0xffffu32));
+        assert_eq!(try_atoi::<u8, 16>(b"ff_ff"), None);
+        assert_eq!(try_atoi::<u8, 16>(b"1ff"), None);
+    }
+
+    proptest! {
+        #[test]
+        fn parse_matches_std(num in any::<i64>()) {
+            prop_assert_eq!(parse_decimal::<i64>(num.to_string().as_bytes()), Ok(num));
+            let hex = format!("{}{:X}", if num < 0 { "-" } else { "+" }, num.unsigned_abs());
+            prop_assert_eq!(parse_int::<i64, 16>(hex.as_bytes()), Ok(num));
+        }
+    }
 }
@@ -0,0 +1,7 @@
+//! Arbitrary-precision integers, for answers that are one tweak away from overflowing u64.
+//!
+//! `fold_decimal_from`, `atoi`, `parse_int` and the [`crate::math`] helpers are generic enough to take these,
+//! and they print with `{}` like any other answer. Swap a day's count type for [`BigUint`] behind its own
+//! `bigint` feature to try more iterations without wrapping.
+
+pub use num::bigint::{BigInt, BigUint, Sign, ToBigInt, ToBigUint};
@@ -0,0 +1,202 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    hash::Hash,
+};
+
+/// Algorithm to use for `find_cycle`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CycleStrategy {
+    /// Tortoise and hare; constant memory, but steps each state up to three times.
+    Floyd,
+    /// Teleporting tortoise; constant memory, fewer steps than Floyd.
+    Brent,
+    /// Remember every state seen; steps each state once, but stores every key.
+    Hashing,
+}
+
+/// Find a cycle in the sequence `init, step(init), step(step(init)), ...`.
+/// Returns (prefix length, cycle length); the state at index `prefix` is the first one which repeats.
+///
+/// The sequence must eventually cycle, or this never returns.
+pub fn find_cycle<S, F>(init: &S, step: F, strategy: CycleStrategy) -> (usize, usize)
+where
+    S: Clone + Eq + Hash,
+    F: Fn(&S) -> S,
+{
+    find_cycle_by_key(init, step, S::clone, strategy)
+}
+
+/// Same as `find_cycle`, but states are compared by `key`.
+/// Useful when only part of the state determines the cycle, e.g. the program counter of a VM.
+pub fn find_cycle_by_key<S, K, F, G>(
+    init: &S,
+    step: F,
+    key: G,
+    strategy: CycleStrategy,
+) -> (usize, usize)
+where
+    S: Clone,
+    K: Eq + Hash,
+    F: Fn(&S) -> S,
+    G: Fn(&S) -> K,
+{
+    match strategy {
+        CycleStrategy::Floyd => floyd(init, step, key),
+        CycleStrategy::Brent => brent(init, step, key),
+        CycleStrategy::Hashing => hashing(init, step, key),
+    }
+}
+
+fn floyd<S, K, F, G>(init: &S, step: F, key: G) -> (usize, usize)
+where
+    S: Clone,
+    K: Eq,
+    F: Fn(&S) -> S,
+    G: Fn(&S) -> K,
+{
+    let mut tortoise = step(init);
+    let mut hare = step(&tortoise);
+    while key(&tortoise) != key(&hare) {
+        tortoise = step(&tortoise);
+        hare = step(&step(&hare));
+    }
+
+    let mut prefix = 0;
+    tortoise = init.clone();
+    while key(&tortoise) != key(&hare) {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix += 1;
+    }
+
+    let mut cycle = 1;
+    hare = step(&tortoise);
+    while key(&tortoise) != key(&hare) {
+        hare = step(&hare);
+        cycle += 1;
+    }
+    (prefix, cycle)
+}
+
+fn brent<S, K, F, G>(init: &S, step: F, key: G) -> (usize, usize)
+where
+    S: Clone,
+    K: Eq,
+    F: Fn(&S) -> S,
+    G: Fn(&S) -> K,
+{
+    let mut power = 1;
+    let mut cycle = 1;
+    let mut tortoise = init.clone();
+    let mut hare = step(init);
+    while key(&tortoise) != key(&hare) {
+        if power == cycle {
+            tortoise = hare.clone();
+            power *= 2;
+            cycle = 0;
+        }
+        hare = step(&hare);
+        cycle += 1;
+    }
+
+    // hare is now `cycle` steps ahead, walk both until they meet at the start of the cycle.
+    let mut prefix = 0;
+    tortoise = init.clone();
+    hare = (0..cycle).fold(init.clone(), |state, _| step(&state));
+    while key(&tortoise) != key(&hare) {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix += 1;
+    }
+    (prefix, cycle)
+}
+
+fn hashing<S, K, F, G>(init: &S, step: F, key: G) -> (usize, usize)
+where
+    S: Clone,
+    K: Eq + Hash,
+    F: Fn(&S) -> S,
+    G: Fn(&S) -> K,
+{
+    let mut seen = HashMap::new();
+    let mut state = init.clone();
+    let mut i = 0;
+    loop {
+        match seen.entry(key(&state)) {
+            Entry::Occupied(first) => return (*first.get(), i - first.get()),
+            Entry::Vacant(slot) => slot.insert(i),
+        };
+        state = step(&state);
+        i += 1;
+    }
+}
+
+/// Get the state at index `n` of the sequence `init, step(init), ...`.
+/// The cycle is found first (with Brent's algorithm), then the remaining whole cycles are skipped,
+/// so `n` can be far larger than could ever be simulated (e.g. a billion spin cycles).
+pub fn nth_state<S, F>(init: &S, step: F, n: usize) -> S
+where
+    S: Clone + Eq + Hash,
+    F: Fn(&S) -> S,
+{
+    let (prefix, cycle) = find_cycle(init, &step, CycleStrategy::Brent);
+    let n = if n < prefix {
+        n
+    } else {
+        prefix + (n - prefix) % cycle
+    };
+    (0..n).fold(init.clone(), |state, _| step(&state))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{find_cycle, find_cycle_by_key, nth_state, CycleStrategy};
+
+    const STRATEGIES: [CycleStrategy; 3] = [
+        CycleStrategy::Floyd,
+        CycleStrategy::Brent,
+        CycleStrategy::Hashing,
+    ];
+
+    // 0, 1, 2, 3, then 4..=10 repeating.
+    fn step(x: &u32) -> u32 {
+        if *x == 10 {
+            4
+        } else {
+            x + 1
+        }
+    }
+
+    #[test]
+    fn cycle_strategies_agree() {
+        for strategy in STRATEGIES {
+            assert_eq!(find_cycle(&0, step, strategy), (4, 7), "{strategy:?}");
+            assert_eq!(find_cycle(&7, step, strategy), (0, 7), "{strategy:?}");
+            assert_eq!(find_cycle(&1, |_| 1, strategy), (0, 1), "{strategy:?}");
+        }
+    }
+
+    #[test]
+    fn cycle_by_key() {
+        // (position, counter) only cycles in position.
+        let step = |&(pos, cnt): &(u32, u32)| (step(&pos), cnt + 1);
+        for strategy in STRATEGIES {
+            assert_eq!(
+                find_cycle_by_key(&(0, 0), step, |s| s.0, strategy),
+                (4, 7),
+                "{strategy:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn nth_state_skips_cycles() {
+        assert_eq!(nth_state(&0, step, 0), 0);
+        assert_eq!(nth_state(&0, step, 3), 3);
+        assert_eq!(nth_state(&0, step, 11), 4);
+        assert_eq!(
+            nth_state(&0, step, 1_000_000_000),
+            4 + (1_000_000_000 - 4) % 7
+        );
+    }
+}
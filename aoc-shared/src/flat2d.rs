@@ -5,7 +5,7 @@ use std::{
     ops::{Index, IndexMut},
 };
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Eq, Hash)]
 pub struct FlatVec2D<T>(pub Vec<T>, pub usize, pub usize);
 
 // Struct that returns a reference inside a FlatVec2D, with its coordinates.
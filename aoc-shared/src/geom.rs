@@ -0,0 +1,591 @@
+use num::{Integer, One, PrimInt, Signed, Zero};
+
+use crate::linalg::Rational;
+
+/// Polygons are slices of vertices in order, the last vertex implicitly connects to the first.
+/// A closing vertex equal to the first one is allowed, it adds an empty edge.
+fn edges<T: Copy>(poly: &[(T, T)]) -> impl Iterator<Item = ((T, T), (T, T))> + '_ {
+    poly.iter()
+        .zip(poly.iter().cycle().skip(1))
+        .map(|(&a, &b)| (a, b))
+}
+
+fn cross<T: PrimInt + Signed>((ax, ay): (T, T), (bx, by): (T, T), (px, py): (T, T)) -> T {
+    (bx - ax) * (py - ay) - (px - ax) * (by - ay)
+}
+
+/// Twice the signed area of a polygon using the shoelace formula; positive if counter-clockwise (y up).
+/// Doubled so the result is always an integer.
+pub fn signed_area2<T: PrimInt + Signed>(poly: &[(T, T)]) -> T {
+    edges(poly).fold(T::zero(), |acc, ((x1, y1), (x2, y2))| {
+        acc + x1 * y2 - x2 * y1
+    })
+}
+
+/// Area of a polygon, rounded down if the polygon has a half unit area.
+pub fn shoelace_area<T: PrimInt + Signed>(poly: &[(T, T)]) -> T {
+    signed_area2(poly).abs() / (T::one() + T::one())
+}
+
+/// Number of lattice points on the edges of a polygon.
+pub fn boundary_points<T: PrimInt + Signed + Integer>(poly: &[(T, T)]) -> T {
+    edges(poly).fold(T::zero(), |acc, ((x1, y1), (x2, y2))| {
+        acc + (x2 - x1).abs().gcd(&(y2 - y1).abs())
+    })
+}
+
+/// Number of lattice points strictly inside a polygon, using Pick's theorem: `A = I + B/2 - 1`.
+/// e.g. the tiles enclosed by a loop of pipes.
+pub fn interior_points<T: PrimInt + Signed + Integer>(poly: &[(T, T)]) -> T {
+    let two = T::one() + T::one();
+    (signed_area2(poly).abs() - boundary_points(poly) + two) / two
+}
+
+/// Number of lattice points inside or on the edges of a polygon.
+/// e.g. the cubic meters of a lagoon where the trench is part of the lagoon.
+pub fn lattice_points<T: PrimInt + Signed + Integer>(poly: &[(T, T)]) -> T {
+    interior_points(poly) + boundary_points(poly)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PointLocation {
+    Inside,
+    Outside,
+    Boundary,
+}
+
+fn on_segment<T: PrimInt + Signed>(a: (T, T), b: (T, T), p: (T, T)) -> bool {
+    cross(a, b, p).is_zero()
+        && a.0.min(b.0) <= p.0
+        && p.0 <= a.0.max(b.0)
+        && a.1.min(b.1) <= p.1
+        && p.1 <= a.1.max(b.1)
+}
+
+/// Locate a point using the even-odd rule; self intersecting polygons alternate inside and outside.
+pub fn locate_even_odd<T: PrimInt + Signed>(poly: &[(T, T)], p: (T, T)) -> PointLocation {
+    let mut inside = false;
+    for (a, b) in edges(poly) {
+        if on_segment(a, b, p) {
+            return PointLocation::Boundary;
+        }
+        if (a.1 > p.1) != (b.1 > p.1) {
+            // the edge crosses the horizontal ray going right of p if p is on the inner side of it.
+            let side = cross(a, b, p);
+            if (b.1 > a.1 && side.is_positive()) || (b.1 < a.1 && side.is_negative()) {
+                inside = !inside;
+            }
+        }
+    }
+    if inside {
+        PointLocation::Inside
+    } else {
+        PointLocation::Outside
+    }
+}
+
+/// How many times the polygon winds around a point, counter-clockwise (y up) is positive.
+/// Points on the boundary are not well defined, see: `locate_winding`.
+pub fn winding_number<T: PrimInt + Signed>(poly: &[(T, T)], p: (T, T)) -> i32 {
+    edges(poly).fold(0, |wn, (a, b)| {
+        if a.1 <= p.1 && p.1 < b.1 && cross(a, b, p).is_positive() {
+            wn + 1
+        } else if b.1 <= p.1 && p.1 < a.1 && cross(a, b, p).is_negative() {
+            wn - 1
+        } else {
+            wn
+        }
+    })
+}
+
+/// Locate a point using the non-zero winding rule.
+pub fn locate_winding<T: PrimInt + Signed>(poly: &[(T, T)], p: (T, T)) -> PointLocation {
+    if edges(poly).any(|(a, b)| on_segment(a, b, p)) {
+        PointLocation::Boundary
+    } else if winding_number(poly, p) != 0 {
+        PointLocation::Inside
+    } else {
+        PointLocation::Outside
+    }
+}
+
+/// Simple polygon made of only horizontal and vertical edges.
+#[derive(Debug, Clone)]
+pub struct RectilinearPolygon<T> {
+    /// Edges as (min corner, max corner).
+    edges: Vec<((T, T), (T, T))>,
+    /// Vertices scaled by two, so the center of any integer rectangle is a lattice point.
+    doubled: Vec<(T, T)>,
+}
+
+impl<T: PrimInt + Signed> RectilinearPolygon<T> {
+    pub fn new(poly: &[(T, T)]) -> Self {
+        let two = T::one() + T::one();
+        let edges = edges(poly)
+            .map(|((x1, y1), (x2, y2))| {
+                assert!(
+                    x1 == x2 || y1 == y2,
+                    "edges must be horizontal or vertical."
+                );
+                ((x1.min(x2), y1.min(y2)), (x1.max(x2), y1.max(y2)))
+            })
+            .collect();
+        let doubled = poly.iter().map(|&(x, y)| (x * two, y * two)).collect();
+        RectilinearPolygon { edges, doubled }
+    }
+
+    pub fn locate(&self, p: (T, T)) -> PointLocation {
+        let two = T::one() + T::one();
+        locate_even_odd(&self.doubled, (p.0 * two, p.1 * two))
+    }
+
+    /// Is the axis aligned rectangle between two opposite corners entirely inside or on the polygon?
+    pub fn contains_rect(&self, (x1, y1): (T, T), (x2, y2): (T, T)) -> bool {
+        let (lo_x, hi_x) = (x1.min(x2), x1.max(x2));
+        let (lo_y, hi_y) = (y1.min(y2), y1.max(y2));
+        // no edge may pass through the inside of the rectangle...
+        let crossed = self
+            .edges
+            .iter()
+            .any(|&((ex1, ey1), (ex2, ey2))| lo_x < ex2 && ex1 < hi_x && lo_y < ey2 && ey1 < hi_y);
+        // ...so it is either entirely in or entirely out, which the center decides.
+        !crossed
+            && locate_even_odd(&self.doubled, (lo_x + hi_x, lo_y + hi_y)) != PointLocation::Outside
+    }
+}
+
+/// Sorted, deduplicated coordinates of one axis, mapping sparse values to dense indices.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompressedAxis<T> {
+    pub values: Vec<T>,
+}
+
+impl<T: Copy + Ord> CompressedAxis<T> {
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Dense index of a value, if it was one of the compressed values.
+    pub fn index(&self, value: T) -> Option<usize> {
+        self.values.binary_search(&value).ok()
+    }
+
+    /// Original value of a dense index.
+    pub fn value(&self, index: usize) -> T {
+        self.values[index]
+    }
+}
+
+impl<T: Copy + Ord> FromIterator<T> for CompressedAxis<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut values = iter.into_iter().collect::<Vec<_>>();
+        values.sort_unstable();
+        values.dedup();
+        CompressedAxis { values }
+    }
+}
+
+/// Compress both axes of a set of points, returns the axes and every point as (x index, y index).
+pub fn compress_coords<T: Copy + Ord>(
+    points: &[(T, T)],
+) -> (CompressedAxis<T>, CompressedAxis<T>, Vec<(usize, usize)>) {
+    let xs = points.iter().map(|p| p.0).collect::<CompressedAxis<_>>();
+    let ys = points.iter().map(|p| p.1).collect::<CompressedAxis<_>>();
+    let compressed = points
+        .iter()
+        .map(|&(x, y)| {
+            (
+                xs.index(x).expect("x to be compressed"),
+                ys.index(y).expect("y to be compressed"),
+            )
+        })
+        .collect();
+    (xs, ys, compressed)
+}
+
+/// How far a `Line` extends along its direction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Extent {
+    /// Every t.
+    Line,
+    /// t >= 0, e.g. a hailstone's future path.
+    Ray,
+    /// 0 <= t <= 1.
+    Segment,
+}
+
+impl Extent {
+    /// (lower, upper) bound of t, None if unbounded.
+    fn bounds(self) -> (Option<Rational>, Option<Rational>) {
+        match self {
+            Extent::Line => (None, None),
+            Extent::Ray => (Some(Rational::zero()), None),
+            Extent::Segment => (Some(Rational::zero()), Some(Rational::one())),
+        }
+    }
+
+    pub fn contains(self, t: &Rational) -> bool {
+        let (lo, hi) = self.bounds();
+        lo.is_none_or(|lo| lo <= *t) && hi.is_none_or(|hi| *t <= hi)
+    }
+}
+
+/// The points `origin + t * dir` in 2 or 3 dimensions, where t is limited by the extent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Line<const N: usize> {
+    pub origin: [i128; N],
+    pub dir: [i128; N],
+    pub extent: Extent,
+}
+
+pub type Line2 = Line<2>;
+pub type Line3 = Line<3>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Intersection<const N: usize> {
+    Disjoint,
+    /// Exactly one common point, which is `self.at(t)` and `other.at(u)`.
+    Point {
+        at: [Rational; N],
+        t: Rational,
+        u: Rational,
+    },
+    /// Collinear and sharing more than one point.
+    Overlap,
+}
+
+fn to3<const N: usize>(v: &[i128; N]) -> [i128; 3] {
+    assert!(N == 2 || N == 3, "only 2D and 3D lines are supported.");
+    let mut ret = [0; 3];
+    ret[..N].copy_from_slice(v);
+    ret
+}
+
+/// Intersections with too large coordinates panic instead of wrapping.
+fn checked(value: Option<i128>) -> i128 {
+    value.expect("coordinates too large for an exact i128 intersection.")
+}
+
+fn sub3(a: [i128; 3], b: [i128; 3]) -> [i128; 3] {
+    std::array::from_fn(|i| checked(a[i].checked_sub(b[i])))
+}
+
+fn dot3(a: [i128; 3], b: [i128; 3]) -> i128 {
+    checked(
+        a.iter()
+            .zip(b)
+            .try_fold(0i128, |acc, (x, y)| acc.checked_add(x.checked_mul(y)?)),
+    )
+}
+
+/// `a * b - c * d`
+fn mul_sub(a: i128, b: i128, c: i128, d: i128) -> i128 {
+    let (ab, cd) = (checked(a.checked_mul(b)), checked(c.checked_mul(d)));
+    checked(ab.checked_sub(cd))
+}
+
+fn cross3(a: [i128; 3], b: [i128; 3]) -> [i128; 3] {
+    [
+        mul_sub(a[1], b[2], a[2], b[1]),
+        mul_sub(a[2], b[0], a[0], b[2]),
+        mul_sub(a[0], b[1], a[1], b[0]),
+    ]
+}
+
+impl<const N: usize> Line<N> {
+    /// Unbounded line through origin.
+    pub fn new(origin: [i128; N], dir: [i128; N]) -> Self {
+        Line {
+            origin,
+            dir,
+            extent: Extent::Line,
+        }
+    }
+
+    pub fn ray(origin: [i128; N], dir: [i128; N]) -> Self {
+        Line {
+            origin,
+            dir,
+            extent: Extent::Ray,
+        }
+    }
+
+    pub fn segment(start: [i128; N], end: [i128; N]) -> Self {
+        let mut dir = end;
+        dir.iter_mut().zip(start).for_each(|(d, s)| *d -= s);
+        Line {
+            origin: start,
+            dir,
+            extent: Extent::Segment,
+        }
+    }
+
+    /// Point at `origin + t * dir`.
+    pub fn at(&self, t: &Rational) -> [Rational; N] {
+        std::array::from_fn(|i| Rational::from(self.origin[i]) + t * self.dir[i])
+    }
+
+    /// Exact intersection, using i128 products of up to four coordinates.
+    ///
+    /// # Panics
+    ///
+    /// If a product overflows, which can happen once a component of either direction or of the
+    /// difference between the origins reaches ~6e9 (`12 * c^4` has to fit an i128).
+    /// Large positions with small directions are fine, e.g. 2023-24 has ~4e14 and ~1e3.
+    pub fn intersect(&self, other: &Self) -> Intersection<N> {
+        let (o1, d1, o2, d2) = (
+            to3(&self.origin),
+            to3(&self.dir),
+            to3(&other.origin),
+            to3(&other.dir),
+        );
+        assert!(
+            d1 != [0; 3] && d2 != [0; 3],
+            "lines need a non-zero direction."
+        );
+        let w = sub3(o2, o1);
+        let n = cross3(d1, d2);
+        if n == [0; 3] {
+            return if cross3(w, d1) == [0; 3] {
+                self.intersect_collinear(other, w, d1, d2)
+            } else {
+                Intersection::Disjoint
+            };
+        }
+        // skew lines in 3D never meet.
+        if dot3(w, n) != 0 {
+            return Intersection::Disjoint;
+        }
+        let nn = dot3(n, n);
+        let t = Rational::new(dot3(cross3(w, d2), n), nn);
+        let u = Rational::new(dot3(cross3(w, d1), n), nn);
+        if self.extent.contains(&t) && other.extent.contains(&u) {
+            Intersection::Point {
+                at: self.at(&t),
+                t,
+                u,
+            }
+        } else {
+            Intersection::Disjoint
+        }
+    }
+
+    fn intersect_collinear(
+        &self,
+        other: &Self,
+        w: [i128; 3],
+        d1: [i128; 3],
+        d2: [i128; 3],
+    ) -> Intersection<N> {
+        // other.at(u) == self.at(a + b * u).
+        let dd = dot3(d1, d1);
+        let a = Rational::new(dot3(w, d1), dd);
+        let b = Rational::new(dot3(d2, d1), dd);
+        let (u_lo, u_hi) = other.extent.bounds();
+        let (mut lo, mut hi) = (u_lo.map(|u| a + b * u), u_hi.map(|u| a + b * u));
+        if b.is_negative() {
+            (lo, hi) = (hi, lo);
+        }
+        let (t_lo, t_hi) = self.extent.bounds();
+        let lo = match (lo, t_lo) {
+            (Some(l), Some(r)) => Some(l.max(r)),
+            (l, r) => l.or(r),
+        };
+        let hi = match (hi, t_hi) {
+            (Some(l), Some(r)) => Some(l.min(r)),
+            (l, r) => l.or(r),
+        };
+        match (lo, hi) {
+            (Some(lo), Some(hi)) if lo > hi => Intersection::Disjoint,
+            (Some(lo), Some(hi)) if lo == hi => Intersection::Point {
+                at: self.at(&lo),
+                t: lo,
+                u: (lo - a) / b,
+            },
+            _ => Intersection::Overlap,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        boundary_points, compress_coords, interior_points, lattice_points, locate_even_odd,
+        locate_winding, shoelace_area, signed_area2, winding_number, Intersection, Line2, Line3,
+        PointLocation, RectilinearPolygon,
+    };
+    use crate::linalg::Rational;
+
+    // 2023-18 example trench.
+    const LAGOON: [(i64, i64); 14] = [
+        (0, 0),
+        (6, 0),
+        (6, 5),
+        (4, 5),
+        (4, 7),
+        (6, 7),
+        (6, 9),
+        (1, 9),
+        (1, 7),
+        (0, 7),
+        (0, 5),
+        (2, 5),
+        (2, 2),
+        (0, 2),
+    ];
+
+    #[test]
+    fn pick() {
+        assert_eq!(boundary_points(&LAGOON), 38);
+        assert_eq!(lattice_points(&LAGOON), 62);
+        assert_eq!(interior_points(&LAGOON), 24);
+        let tri = [(0i64, 0), (4, 0), (0, 3)];
+        assert_eq!(signed_area2(&tri), 12);
+        assert_eq!(shoelace_area(&[(0i64, 0), (0, 3), (4, 0)]), 6);
+        assert_eq!(boundary_points(&tri), 8);
+        assert_eq!(interior_points(&tri), 3);
+    }
+
+    #[test]
+    fn point_in_polygon() {
+        use PointLocation::*;
+        let square = [(0i32, 0), (4, 0), (4, 4), (0, 4)];
+        assert_eq!(locate_even_odd(&square, (2, 2)), Inside);
+        assert_eq!(locate_even_odd(&square, (4, 2)), Boundary);
+        assert_eq!(locate_even_odd(&square, (5, 2)), Outside);
+        assert_eq!(locate_even_odd(&square, (2, 4)), Boundary);
+        assert_eq!(winding_number(&square, (1, 1)), 1);
+        // a square wound twice is inside by winding, but outside by even-odd.
+        let twice = [square, square].concat();
+        assert_eq!(winding_number(&twice, (1, 1)), 2);
+        assert_eq!(locate_winding(&twice, (1, 1)), Inside);
+        assert_eq!(locate_even_odd(&twice, (1, 1)), Outside);
+        // the notch of the lagoon is outside.
+        assert_eq!(locate_winding(&LAGOON, (1, 3)), Outside);
+        assert_eq!(locate_even_odd(&LAGOON, (3, 3)), Inside);
+    }
+
+    #[test]
+    fn rectilinear_rects() {
+        // 2025-09 example.
+        let poly = RectilinearPolygon::new(&[
+            (7i64, 1),
+            (11, 1),
+            (11, 7),
+            (9, 7),
+            (9, 5),
+            (2, 5),
+            (2, 3),
+            (7, 3),
+        ]);
+        assert!(poly.contains_rect((9, 5), (2, 3)));
+        assert!(poly.contains_rect((7, 3), (11, 1)));
+        assert!(!poly.contains_rect((2, 5), (11, 1)));
+        // entirely outside, no edge crosses it.
+        assert!(!poly.contains_rect((2, 1), (6, 2)));
+        assert!(poly.contains_rect((11, 7), (11, 1)));
+        assert_eq!(poly.locate((10, 6)), PointLocation::Inside);
+    }
+
+    #[test]
+    fn compression() {
+        let (xs, ys, points) = compress_coords(&[(100, -5), (3, 7), (100, 7)]);
+        assert_eq!(xs.values, vec![3, 100]);
+        assert_eq!(ys.values, vec![-5, 7]);
+        assert_eq!(points, vec![(1, 0), (0, 1), (1, 1)]);
+        assert_eq!(xs.index(50), None);
+        assert_eq!(ys.value(1), 7);
+    }
+
+    #[test]
+    fn hailstone_rays() {
+        // first two hailstones of the 2023-24 example cross inside the test area.
+        let a = Line2::ray([19, 13], [-2, 1]);
+        let b = Line2::ray([18, 19], [-1, -1]);
+        let Intersection::Point { at, t, u } = a.intersect(&b) else {
+            panic!("expected an intersection");
+        };
+        assert_eq!(at, [Rational::new(43, 3), Rational::new(46, 3)]);
+        assert_eq!((t, u), (Rational::new(7, 3), Rational::new(11, 3)));
+        // crossed in the past for the first hailstone.
+        let c = Line2::ray([20, 19], [1, -5]);
+        assert_eq!(a.intersect(&c), Intersection::Disjoint);
+        // parallel.
+        let d = Line2::ray([20, 25], [-2, -2]);
+        assert_eq!(b.intersect(&d), Intersection::Disjoint);
+    }
+
+    #[test]
+    fn collinear_segments() {
+        let a = Line2::segment([0, 0], [4, 4]);
+        assert_eq!(
+            a.intersect(&Line2::segment([2, 2], [6, 6])),
+            Intersection::Overlap
+        );
+        assert_eq!(
+            a.intersect(&Line2::segment([5, 5], [6, 6])),
+            Intersection::Disjoint
+        );
+        let Intersection::Point { at, t, u } = a.intersect(&Line2::segment([6, 6], [4, 4])) else {
+            panic!("segments touch at one end");
+        };
+        assert_eq!(at, [Rational::from(4), Rational::from(4)]);
+        assert_eq!((t, u), (Rational::from(1), Rational::from(1)));
+        assert_eq!(
+            a.intersect(&Line2::new([-3, -3], [-1, -1])),
+            Intersection::Overlap
+        );
+    }
+
+    #[test]
+    fn lines_3d() {
+        let a = Line3::new([0, 0, 0], [1, 1, 1]);
+        let b = Line3::segment([2, 0, 4], [2, 4, 0]);
+        assert_eq!(
+            a.intersect(&b),
+            Intersection::Point {
+                at: [2, 2, 2].map(Rational::from),
+                t: Rational::from(2),
+                u: Rational::new(1, 2),
+            }
+        );
+        // skew.
+        let c = Line3::new([0, 1, 0], [1, 0, 0]);
+        assert_eq!(a.intersect(&c), Intersection::Disjoint);
+    }
+
+    /// Lines with directions of about `d` per axis, meeting at `origin + dir` of the first one.
+    fn crossing(d: i128) -> (Line3, Line3) {
+        let hit = [d, d - 1, 1];
+        let dir = [d - 3, 5, d];
+        (
+            Line3::new([0; 3], hit),
+            Line3::new([hit[0] - dir[0], hit[1] - dir[1], hit[2] - dir[2]], dir),
+        )
+    }
+
+    #[test]
+    fn near_the_limit() {
+        let d = 1_000_000_000;
+        let (a, b) = crossing(d);
+        assert_eq!(
+            a.intersect(&b),
+            Intersection::Point {
+                at: [d, d - 1, 1].map(Rational::from),
+                t: Rational::from(1),
+                u: Rational::from(1),
+            }
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "too large")]
+    fn past_the_limit() {
+        let (a, b) = crossing(10_000_000_000);
+        a.intersect(&b);
+    }
+}
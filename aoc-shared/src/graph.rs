@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+
+use crate::{Dijkstra, FlatVec2D, HeapState, Neighbor};
+
+type XY = (usize, usize);
+
+/// A weighted graph of the "interesting" cells of a grid maze.
+/// Nodes are junctions (walkable cells with more than two walkable neighbors) plus any user supplied points of interest.
+/// Edges are the lengths of the corridors connecting two nodes.
+#[derive(Debug, Clone, Default)]
+pub struct GridGraph {
+    /// Grid coordinate of each node; the node id is the index into this vector.
+    pub nodes: Vec<XY>,
+    /// Adjacency list of (node id, corridor length), indexed by node id.
+    pub edges: Vec<Vec<(usize, usize)>>,
+}
+
+impl GridGraph {
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Find the node id of a given grid coordinate, if it is a node.
+    pub fn node_id(&self, xy: XY) -> Option<usize> {
+        self.nodes.iter().position(|&n| n == xy)
+    }
+
+    /// Shortest corridor distance between two node ids.
+    pub fn shortest_path(&self, from: usize, to: usize) -> Option<usize> {
+        let mut dij = Dijkstra::<usize, usize>::new();
+        dij.push(from, 0);
+        while let Some(HeapState { key, cost }) = dij.pop() {
+            if key == to {
+                return Some(cost);
+            }
+            self.edges[key]
+                .iter()
+                .for_each(|&(next, dist)| dij.push(next, cost + dist));
+        }
+        None
+    }
+}
+
+/// Compress a grid maze into a `GridGraph`, see: `compress_grid_directed`.
+/// All corridors can be walked in both directions.
+pub fn compress_grid<T, W>(grid: &FlatVec2D<T>, pois: &[XY], walkable: W) -> GridGraph
+where
+    W: Fn(&T) -> bool,
+{
+    compress_grid_directed(grid, pois, walkable, |_, _| true)
+}
+
+/// Compress a grid maze into a `GridGraph` of junctions and points of interest (e.g. start and end).
+///
+/// `walkable` decides which cells are part of the maze, junctions are found using only this predicate.
+/// `can_step(from, to)` decides if a move between two adjacent walkable cells is allowed, (e.g. slopes).
+/// Corridors which contain a disallowed step, or which dead end without reaching a node, produce no edge.
+pub fn compress_grid_directed<T, W, S>(
+    grid: &FlatVec2D<T>,
+    pois: &[XY],
+    walkable: W,
+    can_step: S,
+) -> GridGraph
+where
+    W: Fn(&T) -> bool,
+    S: Fn(XY, XY) -> bool,
+{
+    let mut ids = HashMap::new();
+    let mut nodes = vec![];
+    pois.iter()
+        .copied()
+        .chain(grid.xyrange().filter(|&xy| {
+            walkable(&grid[xy])
+                && grid
+                    .get_neigh_card_iter(xy)
+                    .filter(|Neighbor(cell, _, _)| walkable(cell))
+                    .count()
+                    > 2
+        }))
+        .for_each(|xy| {
+            ids.entry(xy).or_insert_with(|| {
+                nodes.push(xy);
+                nodes.len() - 1
+            });
+        });
+
+    let edges = nodes
+        .iter()
+        .map(|&start| {
+            grid.get_neigh_card_iter(start)
+                .filter(|Neighbor(cell, x, y)| walkable(cell) && can_step(start, (*x, *y)))
+                .filter_map(|Neighbor(_, x, y)| {
+                    let (mut prev, mut curr, mut dist) = (start, (x, y), 1);
+                    loop {
+                        if let Some(&id) = ids.get(&curr) {
+                            return Some((id, dist));
+                        }
+                        // non-node cells have at most one way forward.
+                        let next = grid
+                            .get_neigh_card_iter(curr)
+                            .find(|Neighbor(cell, x, y)| (*x, *y) != prev && walkable(cell))
+                            .map(|Neighbor(_, x, y)| (x, y))?;
+                        if !can_step(curr, next) {
+                            return None;
+                        }
+                        (prev, curr, dist) = (curr, next, dist + 1);
+                    }
+                })
+                .collect()
+        })
+        .collect();
+
+    GridGraph { nodes, edges }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{compress_grid, compress_grid_directed, parse_to_flat2d, FlatVec2D};
+
+    const MAZE: &[u8] = b"\
+#S#####
+#.....#
+#.###.#
+#.#...#
+#.#.#.#
+#...#E#
+#######
+";
+
+    #[test]
+    fn compress_maze() {
+        let grid: FlatVec2D<u8> = parse_to_flat2d(MAZE);
+        let graph = compress_grid(&grid, &[(1, 0), (5, 5)], |&c| c != b'#');
+        // start, end, and junctions at (1, 1) and (5, 3).
+        assert_eq!(graph.len(), 4);
+        let start = graph.node_id((1, 0)).unwrap();
+        let end = graph.node_id((5, 5)).unwrap();
+        assert_eq!(graph.shortest_path(start, end), Some(9));
+        let j = graph.node_id((5, 3)).unwrap();
+        let mut from_j = graph.edges[j].clone();
+        from_j.sort();
+        assert_eq!(
+            from_j,
+            vec![
+                (end, 2),
+                (graph.node_id((1, 1)).unwrap(), 6),
+                (graph.node_id((1, 1)).unwrap(), 10),
+            ]
+        );
+    }
+
+    #[test]
+    fn compress_directed_maze() {
+        let grid: FlatVec2D<u8> = parse_to_flat2d(MAZE);
+        // only allow moving down and right.
+        let graph = compress_grid_directed(
+            &grid,
+            &[(1, 0), (5, 5)],
+            |&c| c != b'#',
+            |(fx, fy), (tx, ty)| tx >= fx && ty >= fy,
+        );
+        let start = graph.node_id((1, 0)).unwrap();
+        let end = graph.node_id((5, 5)).unwrap();
+        assert!(graph.edges[end].is_empty());
+        assert_eq!(graph.shortest_path(start, end), Some(9));
+        assert_eq!(graph.shortest_path(end, start), None);
+    }
+}
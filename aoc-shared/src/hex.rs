@@ -0,0 +1,287 @@
+use std::{
+    collections::{HashMap, HashSet},
+    ops::{Add, AddAssign, Neg, Sub},
+};
+
+/// Axial hex coordinate; the implied third cube coordinate is `s = -q - r`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Hex {
+    pub q: i64,
+    pub r: i64,
+}
+
+impl Hex {
+    pub const ORIGIN: Hex = Hex::new(0, 0);
+
+    pub const fn new(q: i64, r: i64) -> Self {
+        Hex { q, r }
+    }
+
+    /// From cube coordinates, which must sum to zero.
+    pub const fn from_cube(q: i64, r: i64, s: i64) -> Self {
+        assert!(q + r + s == 0, "cube coordinates must sum to zero.");
+        Hex::new(q, r)
+    }
+
+    pub const fn s(self) -> i64 {
+        -self.q - self.r
+    }
+
+    /// (q, r, s) cube coordinates.
+    pub const fn cube(self) -> (i64, i64, i64) {
+        (self.q, self.r, self.s())
+    }
+
+    /// Fewest steps between two hexes.
+    pub const fn distance(self, other: Hex) -> u64 {
+        let dq = self.q.abs_diff(other.q);
+        let dr = self.r.abs_diff(other.r);
+        let ds = self.s().abs_diff(other.s());
+        (dq + dr + ds) / 2
+    }
+
+    pub fn step<D: HexDir>(self, dir: D) -> Hex {
+        self + dir.delta()
+    }
+
+    /// The six adjacent hexes; the same for either orientation.
+    pub fn neighbours(self) -> impl Iterator<Item = Hex> {
+        PointyDir::ALL
+            .into_iter()
+            .map(move |dir| self + dir.delta())
+    }
+}
+
+impl Add for Hex {
+    type Output = Hex;
+
+    fn add(self, rhs: Hex) -> Self::Output {
+        Hex::new(self.q + rhs.q, self.r + rhs.r)
+    }
+}
+
+impl AddAssign for Hex {
+    fn add_assign(&mut self, rhs: Hex) {
+        self.q += rhs.q;
+        self.r += rhs.r;
+    }
+}
+
+impl Sub for Hex {
+    type Output = Hex;
+
+    fn sub(self, rhs: Hex) -> Self::Output {
+        Hex::new(self.q - rhs.q, self.r - rhs.r)
+    }
+}
+
+impl Neg for Hex {
+    type Output = Hex;
+
+    fn neg(self) -> Self::Output {
+        Hex::new(-self.q, -self.r)
+    }
+}
+
+/// The six directions of one hex grid orientation.
+pub trait HexDir: Copy + Sized + 'static {
+    const ALL: [Self; 6];
+
+    /// Offset of one step in this direction.
+    fn delta(self) -> Hex;
+
+    /// Parse a direction name, e.g. `ne`.
+    fn from_name(name: &[u8]) -> Option<Self>;
+}
+
+/// Directions of a flat topped grid, where hexes are stacked in columns: `n, ne, se, s, sw, nw`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FlatDir {
+    N,
+    NE,
+    SE,
+    S,
+    SW,
+    NW,
+}
+
+impl HexDir for FlatDir {
+    const ALL: [Self; 6] = [
+        FlatDir::N,
+        FlatDir::NE,
+        FlatDir::SE,
+        FlatDir::S,
+        FlatDir::SW,
+        FlatDir::NW,
+    ];
+
+    fn delta(self) -> Hex {
+        match self {
+            FlatDir::N => Hex::new(0, -1),
+            FlatDir::NE => Hex::new(1, -1),
+            FlatDir::SE => Hex::new(1, 0),
+            FlatDir::S => Hex::new(0, 1),
+            FlatDir::SW => Hex::new(-1, 1),
+            FlatDir::NW => Hex::new(-1, 0),
+        }
+    }
+
+    fn from_name(name: &[u8]) -> Option<Self> {
+        match name {
+            b"n" => Some(FlatDir::N),
+            b"ne" => Some(FlatDir::NE),
+            b"se" => Some(FlatDir::SE),
+            b"s" => Some(FlatDir::S),
+            b"sw" => Some(FlatDir::SW),
+            b"nw" => Some(FlatDir::NW),
+            _ => None,
+        }
+    }
+}
+
+/// Directions of a pointy topped grid, where hexes are laid out in rows: `e, se, sw, w, nw, ne`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PointyDir {
+    E,
+    SE,
+    SW,
+    W,
+    NW,
+    NE,
+}
+
+impl HexDir for PointyDir {
+    const ALL: [Self; 6] = [
+        PointyDir::E,
+        PointyDir::SE,
+        PointyDir::SW,
+        PointyDir::W,
+        PointyDir::NW,
+        PointyDir::NE,
+    ];
+
+    fn delta(self) -> Hex {
+        match self {
+            PointyDir::E => Hex::new(1, 0),
+            PointyDir::SE => Hex::new(0, 1),
+            PointyDir::SW => Hex::new(-1, 1),
+            PointyDir::W => Hex::new(-1, 0),
+            PointyDir::NW => Hex::new(0, -1),
+            PointyDir::NE => Hex::new(1, -1),
+        }
+    }
+
+    fn from_name(name: &[u8]) -> Option<Self> {
+        match name {
+            b"e" => Some(PointyDir::E),
+            b"se" => Some(PointyDir::SE),
+            b"sw" => Some(PointyDir::SW),
+            b"w" => Some(PointyDir::W),
+            b"nw" => Some(PointyDir::NW),
+            b"ne" => Some(PointyDir::NE),
+            _ => None,
+        }
+    }
+}
+
+/// Parse a walk of steps, either separated, like `ne,ne,s`, or run together, like `esenee`.
+/// Commas and whitespace are skipped, two letter names are preferred over one letter names.
+/// On error, returns the index of the byte that could not be parsed.
+pub fn parse_steps<D: HexDir>(input: &[u8]) -> Result<Vec<D>, usize> {
+    let mut ret = vec![];
+    let mut i = 0;
+    while i < input.len() {
+        if input[i] == b',' || input[i].is_ascii_whitespace() {
+            i += 1;
+            continue;
+        }
+        let (dir, len) = input
+            .get(i..i + 2)
+            .and_then(D::from_name)
+            .map(|dir| (dir, 2))
+            .or_else(|| D::from_name(&input[i..i + 1]).map(|dir| (dir, 1)))
+            .ok_or(i)?;
+        ret.push(dir);
+        i += len;
+    }
+    Ok(ret)
+}
+
+/// Follow a walk of steps from the origin.
+pub fn walk<D: HexDir>(steps: &[D]) -> Hex {
+    steps.iter().fold(Hex::ORIGIN, |pos, &dir| pos.step(dir))
+}
+
+/// One generation of a cellular automaton over an infinite hex grid of live cells.
+/// `rule(alive, live_neighbours)` decides if a cell is alive in the next generation.
+pub fn automaton_step<F>(live: &HashSet<Hex>, rule: F) -> HashSet<Hex>
+where
+    F: Fn(bool, usize) -> bool,
+{
+    let mut counts: HashMap<Hex, usize> = live.iter().map(|&hex| (hex, 0)).collect();
+    live.iter()
+        .flat_map(|hex| hex.neighbours())
+        .for_each(|hex| *counts.entry(hex).or_default() += 1);
+    counts
+        .into_iter()
+        .filter(|(hex, count)| rule(live.contains(hex), *count))
+        .map(|(hex, _)| hex)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{automaton_step, parse_steps, walk, FlatDir, Hex, PointyDir};
+    use std::collections::HashSet;
+
+    #[test]
+    fn flat_walks() {
+        // 2017-11 examples.
+        for (steps, dist) in [
+            ("ne,ne,ne", 3),
+            ("ne,ne,sw,sw", 0),
+            ("ne,ne,s,s", 2),
+            ("se,sw,se,sw,sw", 3),
+        ] {
+            let steps = parse_steps::<FlatDir>(steps.as_bytes()).unwrap();
+            assert_eq!(walk(&steps).distance(Hex::ORIGIN), dist, "{steps:?}");
+        }
+        assert_eq!(parse_steps::<FlatDir>(b"n,e"), Err(2));
+    }
+
+    #[test]
+    fn pointy_walks() {
+        let steps = parse_steps::<PointyDir>(b"esenee").unwrap();
+        assert_eq!(
+            steps,
+            vec![PointyDir::E, PointyDir::SE, PointyDir::NE, PointyDir::E]
+        );
+        assert_eq!(walk(&steps), Hex::new(3, 0));
+        assert_eq!(
+            walk(&parse_steps::<PointyDir>(b"nwwswee").unwrap()),
+            Hex::ORIGIN
+        );
+        assert_eq!(Hex::from_cube(1, 2, -3).cube(), (1, 2, -3));
+        assert_eq!(Hex::ORIGIN.neighbours().count(), 6);
+        assert!(Hex::new(2, -1)
+            .neighbours()
+            .all(|n| n.distance(Hex::new(2, -1)) == 1));
+    }
+
+    #[test]
+    fn automaton() {
+        let rule = |alive, n| matches!((alive, n), (true, 1 | 2) | (false, 2));
+        // A lone cell has no live neighbours and dies.
+        let lone = HashSet::from([Hex::ORIGIN]);
+        assert!(automaton_step(&lone, rule).is_empty());
+        // A live pair survives and spawns the two cells adjacent to both.
+        let tiles = ["nwwswee", "esew"]
+            .map(|line| walk(&parse_steps::<PointyDir>(line.as_bytes()).unwrap()));
+        let pair = HashSet::from(tiles);
+        assert_eq!(pair, HashSet::from([Hex::ORIGIN, Hex::new(0, 1)]));
+        assert_eq!(
+            automaton_step(&pair, rule),
+            HashSet::from([Hex::ORIGIN, Hex::new(0, 1), Hex::new(-1, 1), Hex::new(1, 0)])
+        );
+    }
+}
@@ -0,0 +1,465 @@
+use std::fmt::Display;
+
+use num::{Integer, ToPrimitive};
+
+use crate::linalg::{BigRational, Field};
+
+/// Relation of a linear constraint to its right hand side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cmp {
+    Le,
+    Eq,
+    Ge,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Constraint {
+    /// (variable, coefficient) pairs.
+    pub coeffs: Vec<(usize, i64)>,
+    pub cmp: Cmp,
+    pub rhs: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bounds {
+    pub lo: i64,
+    pub hi: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IlpSolution {
+    pub objective: i64,
+    pub values: Vec<i64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IlpError {
+    /// No integer assignment satisfies every constraint and bound.
+    Infeasible,
+    /// The LP relaxation of the whole problem is unbounded, so the objective can be improved forever
+    /// unless there is no integer solution at all, which is not checked.
+    Unbounded,
+}
+
+impl Display for IlpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IlpError::Infeasible => f.write_str("integer program is infeasible"),
+            IlpError::Unbounded => f.write_str("integer program is unbounded"),
+        }
+    }
+}
+
+impl std::error::Error for IlpError {}
+
+/// Small integer linear program, solved with branch and bound over an exact two-phase simplex.
+///
+/// # Examples
+///
+/// ```
+/// use aoc_shared::ilp::{Cmp, Ilp};
+///
+/// let mut ilp = Ilp::new();
+/// let x = ilp.add_var(0, None);
+/// let y = ilp.add_var(0, None);
+/// ilp.add_constraint(&[(x, 1), (y, 1)], Cmp::Eq, 7);
+/// ilp.add_constraint(&[(x, 1)], Cmp::Ge, 3);
+/// ilp.minimize(&[(x, 2), (y, 1)]);
+/// let sol = ilp.solve().unwrap();
+/// assert_eq!(sol.values, vec![3, 4]);
+/// assert_eq!(sol.objective, 10);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Ilp {
+    pub bounds: Vec<Bounds>,
+    pub constraints: Vec<Constraint>,
+    /// Objective coefficient per variable, always minimized.
+    objective: Vec<i64>,
+    maximize: bool,
+}
+
+impl Ilp {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an integer variable with an inclusive lower and optional upper bound, returns its index.
+    pub fn add_var(&mut self, lo: i64, hi: Option<i64>) -> usize {
+        self.bounds.push(Bounds { lo, hi });
+        self.objective.push(0);
+        self.bounds.len() - 1
+    }
+
+    pub fn add_constraint(&mut self, coeffs: &[(usize, i64)], cmp: Cmp, rhs: i64) {
+        self.constraints.push(Constraint {
+            coeffs: coeffs.to_vec(),
+            cmp,
+            rhs,
+        });
+    }
+
+    pub fn minimize(&mut self, coeffs: &[(usize, i64)]) {
+        self.set_objective(coeffs, false);
+    }
+
+    pub fn maximize(&mut self, coeffs: &[(usize, i64)]) {
+        self.set_objective(coeffs, true);
+    }
+
+    fn set_objective(&mut self, coeffs: &[(usize, i64)], maximize: bool) {
+        self.maximize = maximize;
+        self.objective.iter_mut().for_each(|c| *c = 0);
+        coeffs
+            .iter()
+            .for_each(|&(var, c)| self.objective[var] += if maximize { -c } else { c });
+    }
+
+    /// Solve using big integer rationals, which can never overflow, see: `solve_with`.
+    pub fn solve(&self) -> Result<IlpSolution, IlpError> {
+        self.solve_with::<BigRational>()
+    }
+
+    /// Depth first branch and bound, each node solves the LP relaxation with tightened bounds.
+    ///
+    /// Pivoting can grow numerators and denominators well past the inputs, and a fixed width field
+    /// such as [`Rational`](crate::linalg::Rational) does unchecked arithmetic: it panics on overflow in
+    /// debug builds and gives wrong answers in release. Only use one when the problem is known to be tiny.
+    pub fn solve_with<T: Field + Integral>(&self) -> Result<IlpSolution, IlpError> {
+        let mut best: Option<IlpSolution> = None;
+        let mut stack = vec![self.bounds.clone()];
+        let mut root = true;
+        while let Some(bounds) = stack.pop() {
+            let (lp_obj, values) = match self.relaxation::<T>(&bounds) {
+                Ok(lp) => lp,
+                Err(IlpError::Unbounded) if root => return Err(IlpError::Unbounded),
+                // a branch of a bounded root can't be unbounded, only infeasible.
+                Err(_) => continue,
+            };
+            root = false;
+            // integer objective coefficients make any integer solution's objective integral.
+            if best
+                .as_ref()
+                .is_some_and(|b| lp_obj.ceil_int() >= b.objective)
+            {
+                continue;
+            }
+            match values.iter().position(|v| !v.is_integral()) {
+                None => {
+                    let values = values.iter().map(T::ceil_int).collect::<Vec<_>>();
+                    let objective = values.iter().zip(&self.objective).map(|(v, c)| v * c).sum();
+                    best = Some(IlpSolution { objective, values });
+                }
+                Some(var) => {
+                    let mut down = bounds.clone();
+                    down[var].hi = Some(values[var].floor_int());
+                    let mut up = bounds;
+                    up[var].lo = values[var].ceil_int();
+                    stack.push(up);
+                    stack.push(down);
+                }
+            }
+        }
+        best.map(|mut sol| {
+            if self.maximize {
+                sol.objective = -sol.objective;
+            }
+            sol
+        })
+        .ok_or(IlpError::Infeasible)
+    }
+
+    /// Solve the LP relaxation, returns (objective, values).
+    fn relaxation<T: Field>(&self, bounds: &[Bounds]) -> Result<(T, Vec<T>), IlpError> {
+        let vars = bounds.len();
+        if bounds.iter().any(|b| b.hi.is_some_and(|hi| hi < b.lo)) {
+            return Err(IlpError::Infeasible);
+        }
+        // shift every variable by its lower bound, so y = x - lo >= 0.
+        let mut rows: Vec<(Vec<T>, Cmp, T)> = self
+            .constraints
+            .iter()
+            .map(|c| {
+                let mut coeffs = vec![T::zero(); vars];
+                let mut rhs = c.rhs as i128;
+                c.coeffs.iter().for_each(|&(var, a)| {
+                    coeffs[var] = coeffs[var].clone() + int::<T>(a as i128);
+                    rhs -= a as i128 * bounds[var].lo as i128;
+                });
+                (coeffs, c.cmp, int(rhs))
+            })
+            .collect();
+        bounds.iter().enumerate().for_each(|(var, b)| {
+            if let Some(hi) = b.hi {
+                let mut coeffs = vec![T::zero(); vars];
+                coeffs[var] = T::one();
+                rows.push((coeffs, Cmp::Le, int(hi as i128 - b.lo as i128)));
+            }
+        });
+        let cost = self
+            .objective
+            .iter()
+            .map(|&c| int(c as i128))
+            .collect::<Vec<T>>();
+        let (obj, y) = simplex(vars, rows, &cost)?;
+        let offset = self
+            .objective
+            .iter()
+            .zip(bounds)
+            .fold(0i128, |acc, (&c, b)| acc + c as i128 * b.lo as i128);
+        let x = y
+            .into_iter()
+            .zip(bounds)
+            .map(|(y, b)| y + int(b.lo as i128))
+            .collect();
+        Ok((obj + int(offset), x))
+    }
+}
+
+/// Rounding helpers for the rationals used by `Ilp`.
+pub trait Integral {
+    fn is_integral(&self) -> bool;
+    fn floor_int(&self) -> i64;
+    fn ceil_int(&self) -> i64;
+}
+
+impl<I> Integral for num::rational::Ratio<I>
+where
+    I: Clone + Integer + ToPrimitive,
+{
+    fn is_integral(&self) -> bool {
+        self.is_integer()
+    }
+
+    fn floor_int(&self) -> i64 {
+        self.floor()
+            .to_integer()
+            .to_i64()
+            .expect("value to fit i64")
+    }
+
+    fn ceil_int(&self) -> i64 {
+        self.ceil().to_integer().to_i64().expect("value to fit i64")
+    }
+}
+
+fn int<T: Field>(n: i128) -> T {
+    T::from_i128(n).expect("integer to fit the field type.")
+}
+
+/// Dense simplex tableau, each row ends with its right hand side.
+struct Tableau<T> {
+    rows: Vec<Vec<T>>,
+    basis: Vec<usize>,
+}
+
+impl<T: Field> Tableau<T> {
+    fn rhs(&self, r: usize) -> &T {
+        self.rows[r].last().expect("non-empty row")
+    }
+
+    fn pivot(&mut self, r: usize, c: usize) {
+        let inv = T::one() / self.rows[r][c].clone();
+        self.rows[r]
+            .iter_mut()
+            .for_each(|v| *v = v.clone() * inv.clone());
+        let pivot_row = self.rows[r].clone();
+        for (i, row) in self.rows.iter_mut().enumerate() {
+            let factor = row[c].clone();
+            if i == r || factor.is_zero() {
+                continue;
+            }
+            row.iter_mut()
+                .zip(&pivot_row)
+                .for_each(|(v, p)| *v = v.clone() - factor.clone() * p.clone());
+        }
+        self.basis[r] = c;
+    }
+
+    /// Minimize cost over the columns marked `allowed`, using Bland's rule to avoid cycling.
+    fn optimize(&mut self, cost: &[T], allowed: &[bool]) -> Result<(), IlpError> {
+        loop {
+            let entering = (0..cost.len()).filter(|&c| allowed[c]).find(|&c| {
+                let reduced = self
+                    .rows
+                    .iter()
+                    .zip(&self.basis)
+                    .fold(cost[c].clone(), |acc, (row, &b)| {
+                        acc - cost[b].clone() * row[c].clone()
+                    });
+                reduced.is_negative()
+            });
+            let Some(c) = entering else {
+                return Ok(());
+            };
+            let leaving = (0..self.rows.len())
+                .filter(|&r| self.rows[r][c].is_positive())
+                .map(|r| (self.rhs(r).clone() / self.rows[r][c].clone(), r))
+                .reduce(|best, cand| {
+                    let diff = cand.0.clone() - best.0.clone();
+                    if diff.is_negative()
+                        || (diff.is_zero() && self.basis[cand.1] < self.basis[best.1])
+                    {
+                        cand
+                    } else {
+                        best
+                    }
+                });
+            let Some((_, r)) = leaving else {
+                return Err(IlpError::Unbounded);
+            };
+            self.pivot(r, c);
+        }
+    }
+
+    fn objective(&self, cost: &[T]) -> T {
+        self.basis
+            .iter()
+            .enumerate()
+            .fold(T::zero(), |acc, (r, &b)| {
+                acc + cost[b].clone() * self.rhs(r).clone()
+            })
+    }
+}
+
+/// Two-phase simplex minimizing `cost * y` subject to `rows` and `y >= 0`.
+fn simplex<T: Field>(
+    vars: usize,
+    rows: Vec<(Vec<T>, Cmp, T)>,
+    cost: &[T],
+) -> Result<(T, Vec<T>), IlpError> {
+    let slacks = rows.iter().filter(|(_, cmp, _)| *cmp != Cmp::Eq).count();
+    let arts = rows.len();
+    let width = vars + slacks + arts;
+    let mut slack = vars;
+    let mut tableau = Tableau {
+        rows: Vec::with_capacity(rows.len()),
+        basis: Vec::with_capacity(rows.len()),
+    };
+    for (i, (coeffs, cmp, rhs)) in rows.into_iter().enumerate() {
+        let mut row = coeffs;
+        row.resize(width + 1, T::zero());
+        match cmp {
+            Cmp::Le => row[slack] = T::one(),
+            Cmp::Ge => row[slack] = -T::one(),
+            Cmp::Eq => (),
+        }
+        if cmp != Cmp::Eq {
+            slack += 1;
+        }
+        row[width] = rhs;
+        if row[width].is_negative() {
+            row.iter_mut().for_each(|v| *v = -v.clone());
+        }
+        row[vars + slacks + i] = T::one();
+        tableau.rows.push(row);
+        tableau.basis.push(vars + slacks + i);
+    }
+
+    // phase 1: minimize the artificial variables to find a feasible basis.
+    let mut phase1 = vec![T::zero(); width];
+    phase1[vars + slacks..]
+        .iter_mut()
+        .for_each(|c| *c = T::one());
+    tableau.optimize(&phase1, &vec![true; width])?;
+    if !tableau.objective(&phase1).is_zero() {
+        return Err(IlpError::Infeasible);
+    }
+    // drive any remaining (zero valued) artificials out of the basis, or drop redundant rows.
+    let mut r = 0;
+    while r < tableau.rows.len() {
+        if tableau.basis[r] >= vars + slacks {
+            match (0..vars + slacks).find(|&c| !tableau.rows[r][c].is_zero()) {
+                Some(c) => tableau.pivot(r, c),
+                None => {
+                    tableau.rows.remove(r);
+                    tableau.basis.remove(r);
+                    continue;
+                }
+            }
+        }
+        r += 1;
+    }
+
+    // phase 2: minimize the real objective without the artificials.
+    let mut phase2 = cost.to_vec();
+    phase2.resize(width, T::zero());
+    let allowed = (0..width).map(|c| c < vars + slacks).collect::<Vec<_>>();
+    tableau.optimize(&phase2, &allowed)?;
+
+    let mut values = vec![T::zero(); vars];
+    tableau
+        .basis
+        .iter()
+        .enumerate()
+        .filter(|(_, &b)| b < vars)
+        .for_each(|(r, &b)| values[b] = tableau.rhs(r).clone());
+    Ok((tableau.objective(&phase2), values))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Cmp, Ilp, IlpError};
+    use crate::linalg::Rational;
+
+    /// 2025-10 example machine: buttons (3) (1,3) (2) (2,3) (0,2) (0,1) with joltage {3,5,4,7}
+    fn joltage_machine() -> Ilp {
+        let buttons: [&[usize]; 6] = [&[3], &[1, 3], &[2], &[2, 3], &[0, 2], &[0, 1]];
+        let jolts = [3, 5, 4, 7];
+        let mut ilp = Ilp::new();
+        let vars = buttons
+            .iter()
+            .map(|_| ilp.add_var(0, None))
+            .collect::<Vec<_>>();
+        jolts.iter().enumerate().for_each(|(counter, &jolt)| {
+            let coeffs = buttons
+                .iter()
+                .zip(&vars)
+                .filter(|(btn, _)| btn.contains(&counter))
+                .map(|(_, &v)| (v, 1))
+                .collect::<Vec<_>>();
+            ilp.add_constraint(&coeffs, Cmp::Eq, jolt);
+        });
+        ilp.minimize(&vars.iter().map(|&v| (v, 1)).collect::<Vec<_>>());
+        ilp
+    }
+
+    #[test]
+    fn minimize_presses() {
+        let ilp = joltage_machine();
+        let sol = ilp.solve().expect("feasible");
+        assert_eq!(sol.objective, 10);
+        let small = ilp.solve_with::<Rational>().expect("feasible");
+        assert_eq!(small.objective, 10);
+    }
+
+    #[test]
+    fn branching_needed() {
+        // LP optimum is fractional (x = 1.5, y = 2), integer optimum is x = 1, y = 2 or x = 2, y = 1.
+        let mut ilp = Ilp::new();
+        let x = ilp.add_var(0, Some(10));
+        let y = ilp.add_var(0, Some(10));
+        ilp.add_constraint(&[(x, 2), (y, 2)], Cmp::Le, 7);
+        ilp.add_constraint(&[(y, 1)], Cmp::Le, 2);
+        ilp.maximize(&[(x, 1), (y, 1)]);
+        let sol = ilp.solve().expect("feasible");
+        assert_eq!(sol.objective, 3);
+        assert!(sol.values[0] * 2 + sol.values[1] * 2 <= 7);
+    }
+
+    #[test]
+    fn infeasible_and_unbounded() {
+        let mut ilp = Ilp::new();
+        let x = ilp.add_var(0, None);
+        ilp.add_constraint(&[(x, 2)], Cmp::Eq, 3);
+        ilp.minimize(&[(x, 1)]);
+        assert_eq!(ilp.solve(), Err(IlpError::Infeasible));
+
+        let mut ilp = Ilp::new();
+        let x = ilp.add_var(-5, None);
+        ilp.add_constraint(&[(x, 1)], Cmp::Ge, -2);
+        ilp.maximize(&[(x, 1)]);
+        assert_eq!(ilp.solve(), Err(IlpError::Unbounded));
+        ilp.minimize(&[(x, 1)]);
+        assert_eq!(ilp.solve().map(|s| s.values), Ok(vec![-2]));
+    }
+}
@@ -0,0 +1,365 @@
+use std::ops::Range;
+
+use num::Num;
+
+/// Set of values stored as sorted, disjoint, half-open ranges.
+/// Overlapping or touching ranges are merged on insert.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntervalSet<T> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        IntervalSet { ranges: vec![] }
+    }
+}
+
+impl<T> IntervalSet<T>
+where
+    T: Copy + Ord + Num,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert a range, merging it with any range it overlaps or touches.
+    pub fn insert(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+        // first range that ends at or after our start can merge with us.
+        let lo = self.ranges.partition_point(|r| r.end < range.start);
+        // first range that starts after our end cannot.
+        let hi = self.ranges.partition_point(|r| r.start <= range.end);
+        let merged = if lo < hi {
+            range.start.min(self.ranges[lo].start)..range.end.max(self.ranges[hi - 1].end)
+        } else {
+            range
+        };
+        self.ranges.splice(lo..hi, [merged]);
+    }
+
+    /// Insert an inclusive range, e.g. AoC style `3-5` ranges.
+    pub fn insert_inclusive(&mut self, start: T, end: T) {
+        self.insert(start..end + T::one());
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        let i = self.ranges.partition_point(|r| r.end <= *value);
+        self.ranges.get(i).is_some_and(|r| r.contains(value))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Number of disjoint ranges in the set.
+    pub fn num_ranges(&self) -> usize {
+        self.ranges.len()
+    }
+
+    /// Total amount of values covered by the set.
+    pub fn total_len(&self) -> T {
+        self.ranges
+            .iter()
+            .fold(T::zero(), |acc, r| acc + (r.end - r.start))
+    }
+
+    /// Smallest value in the set.
+    pub fn min(&self) -> Option<T> {
+        self.ranges.first().map(|r| r.start)
+    }
+
+    /// Largest value in the set.
+    pub fn max(&self) -> Option<T> {
+        self.ranges.last().map(|r| r.end - T::one())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Range<T>> {
+        self.ranges.iter()
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut ret = self.clone();
+        other.ranges.iter().for_each(|r| ret.insert(r.clone()));
+        ret
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = vec![];
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (l, r) = (&self.ranges[i], &other.ranges[j]);
+            let start = l.start.max(r.start);
+            let end = l.end.min(r.end);
+            if start < end {
+                ranges.push(start..end);
+            }
+            if l.end < r.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        IntervalSet { ranges }
+    }
+
+    /// Values in self that are not in other.
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut ranges = vec![];
+        let mut j = 0;
+        for range in &self.ranges {
+            let mut start = range.start;
+            // skip the ranges of other that end before this one starts.
+            while j < other.ranges.len() && other.ranges[j].end <= start {
+                j += 1;
+            }
+            let mut k = j;
+            while k < other.ranges.len() && other.ranges[k].start < range.end {
+                let cut = &other.ranges[k];
+                if start < cut.start {
+                    ranges.push(start..cut.start);
+                }
+                start = start.max(cut.end);
+                k += 1;
+            }
+            if start < range.end {
+                ranges.push(start..range.end);
+            }
+        }
+        IntervalSet { ranges }
+    }
+
+    /// Split into values less than `at` and values greater than or equal to `at`.
+    pub fn split_at(&self, at: T) -> (Self, Self) {
+        let mut lower = vec![];
+        let mut upper = vec![];
+        for r in &self.ranges {
+            if r.end <= at {
+                lower.push(r.clone());
+            } else if at <= r.start {
+                upper.push(r.clone());
+            } else {
+                lower.push(r.start..at);
+                upper.push(at..r.end);
+            }
+        }
+        (IntervalSet { ranges: lower }, IntervalSet { ranges: upper })
+    }
+}
+
+impl<T> FromIterator<Range<T>> for IntervalSet<T>
+where
+    T: Copy + Ord + Num,
+{
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        let mut ret = IntervalSet::new();
+        iter.into_iter().for_each(|r| ret.insert(r));
+        ret
+    }
+}
+
+impl<T> From<Range<T>> for IntervalSet<T>
+where
+    T: Copy + Ord + Num,
+{
+    fn from(range: Range<T>) -> Self {
+        let mut ret = IntervalSet::new();
+        ret.insert(range);
+        ret
+    }
+}
+
+/// Piecewise translation of values, e.g. an almanac layer of `dst src len` mappings.
+/// Values inside a source range are moved to the same place in its destination, any other value
+/// maps to itself. Works for unsigned `T`, destinations may be below their sources.
+#[derive(Debug, Clone, Default)]
+pub struct RangeMap<T> {
+    /// (source range, destination start); earlier entries win if sources overlap.
+    pub entries: Vec<(Range<T>, T)>,
+}
+
+impl<T> RangeMap<T>
+where
+    T: Copy + Ord + Num,
+{
+    pub fn new() -> Self {
+        RangeMap { entries: vec![] }
+    }
+
+    /// Map `source` onto the range of the same length starting at `dst`.
+    pub fn insert(&mut self, source: Range<T>, dst: T) {
+        self.entries.push((source, dst));
+    }
+
+    /// Insert a mapping of `len` values starting at `src` to `dst`.
+    pub fn insert_dst_src_len(&mut self, dst: T, src: T, len: T) {
+        self.insert(src..src + len, dst);
+    }
+
+    pub fn map_value(&self, value: T) -> T {
+        self.entries
+            .iter()
+            .find(|(source, _)| source.contains(&value))
+            .map_or(value, |&(ref source, dst)| value - source.start + dst)
+    }
+
+    pub fn map_set(&self, set: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut unmapped = set.clone();
+        let mut ret = IntervalSet::new();
+        for &(ref source, dst) in &self.entries {
+            let to_dst = |value: T| value - source.start + dst;
+            let source = IntervalSet::from(source.clone());
+            unmapped
+                .intersection(&source)
+                .iter()
+                .for_each(|r| ret.insert(to_dst(r.start)..to_dst(r.end)));
+            unmapped = unmapped.difference(&source);
+        }
+        ret.union(&unmapped)
+    }
+}
+
+/// N-dimensional box of half-open ranges, e.g. cuboids or hypercubes of part ratings.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IntervalBox<T, const N: usize>(pub [Range<T>; N]);
+
+impl<T, const N: usize> IntervalBox<T, N>
+where
+    T: Copy + Ord + Num,
+{
+    /// Build a box from inclusive (start, end) bounds per axis.
+    pub fn from_inclusive(bounds: [(T, T); N]) -> Self {
+        IntervalBox(bounds.map(|(start, end)| start..end + T::one()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().any(|r| r.is_empty())
+    }
+
+    pub fn contains(&self, point: &[T; N]) -> bool {
+        self.0.iter().zip(point).all(|(r, p)| r.contains(p))
+    }
+
+    /// Amount of points inside of the box.
+    pub fn volume(&self) -> T {
+        if self.is_empty() {
+            return T::zero();
+        }
+        self.0
+            .iter()
+            .fold(T::one(), |acc, r| acc * (r.end - r.start))
+    }
+
+    pub fn intersect(&self, other: &Self) -> Option<Self> {
+        let mut ret = self.clone();
+        ret.0.iter_mut().zip(&other.0).for_each(|(l, r)| {
+            *l = l.start.max(r.start)..l.end.min(r.end);
+        });
+        (!ret.is_empty()).then_some(ret)
+    }
+
+    /// Split along `axis` into the part below `at` and the part at or above `at`.
+    pub fn split_at(&self, axis: usize, at: T) -> (Option<Self>, Option<Self>) {
+        let mut lower = self.clone();
+        let mut upper = self.clone();
+        let r = &self.0[axis];
+        lower.0[axis] = r.start..at.clamp(r.start, r.end);
+        upper.0[axis] = at.clamp(r.start, r.end)..r.end;
+        (
+            (!lower.is_empty()).then_some(lower),
+            (!upper.is_empty()).then_some(upper),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{IntervalBox, IntervalSet, RangeMap};
+
+    #[test]
+    fn interval_merge() {
+        let mut set = IntervalSet::new();
+        set.insert_inclusive(3, 5);
+        set.insert_inclusive(10, 14);
+        set.insert_inclusive(16, 20);
+        set.insert_inclusive(12, 18);
+        assert_eq!(set.iter().cloned().collect::<Vec<_>>(), vec![3..6, 10..21]);
+        assert_eq!(set.total_len(), 14);
+        assert!(set.contains(&5));
+        assert!(!set.contains(&8));
+        assert!(set.contains(&17));
+        assert!(!set.contains(&32));
+        // touching ranges merge
+        set.insert(6..10);
+        assert_eq!(set.num_ranges(), 1);
+        assert_eq!((set.min(), set.max()), (Some(3), Some(20)));
+    }
+
+    #[test]
+    fn interval_algebra() {
+        let a: IntervalSet<i32> = [0..10, 20..30].into_iter().collect();
+        let b: IntervalSet<i32> = [5..25, 28..40].into_iter().collect();
+        let ranges = |s: IntervalSet<i32>| s.iter().cloned().collect::<Vec<_>>();
+        assert_eq!(ranges(a.union(&b)), vec![0..40]);
+        assert_eq!(ranges(a.intersection(&b)), vec![5..10, 20..25, 28..30]);
+        assert_eq!(ranges(a.difference(&b)), vec![0..5, 25..28]);
+        assert_eq!(ranges(b.difference(&a)), vec![10..20, 30..40]);
+        let (lo, hi) = a.split_at(25);
+        assert_eq!(ranges(lo), vec![0..10, 20..25]);
+        assert_eq!(ranges(hi), vec![25..30]);
+    }
+
+    #[test]
+    fn range_map_layers() {
+        // seed-to-soil map of the 2023-05 example.
+        let mut map = RangeMap::new();
+        map.insert_dst_src_len(50, 98, 2);
+        map.insert_dst_src_len(52, 50, 48);
+        assert_eq!(map.map_value(79), 81);
+        assert_eq!(map.map_value(14), 14);
+        assert_eq!(map.map_value(99), 51);
+        let seeds: IntervalSet<i64> = [79..93, 55..68].into_iter().collect();
+        let soil = map.map_set(&seeds);
+        assert_eq!(
+            soil.iter().cloned().collect::<Vec<_>>(),
+            vec![57..70, 81..95]
+        );
+        let wide = map.map_set(&IntervalSet::from(40..100));
+        assert_eq!(wide.iter().cloned().collect::<Vec<_>>(), vec![40..100]);
+        assert_eq!(wide.total_len(), 60);
+    }
+
+    #[test]
+    fn range_map_unsigned() {
+        // destinations below their sources must not underflow.
+        let mut map = RangeMap::<u64>::new();
+        map.insert_dst_src_len(50, 98, 2);
+        map.insert_dst_src_len(0, 10, 5);
+        assert_eq!(map.map_value(99), 51);
+        assert_eq!(map.map_value(12), 2);
+        assert_eq!(map.map_value(7), 7);
+        let mapped = map.map_set(&[8..12, 97..100].into_iter().collect());
+        assert_eq!(
+            mapped.iter().cloned().collect::<Vec<_>>(),
+            vec![0..2, 8..10, 50..52, 97..98]
+        );
+    }
+
+    #[test]
+    fn interval_box() {
+        let a = IntervalBox::from_inclusive([(10, 12), (10, 12), (10, 12)]);
+        let b = IntervalBox::from_inclusive([(11, 13), (11, 13), (11, 13)]);
+        assert_eq!(a.volume(), 27);
+        assert_eq!(a.intersect(&b).map(|i| i.volume()), Some(8));
+        assert!(a.contains(&[12, 10, 11]));
+        assert!(!a.contains(&[13, 10, 11]));
+        let far = IntervalBox::from_inclusive([(20, 21), (0, 1), (0, 1)]);
+        assert_eq!(a.intersect(&far), None);
+        let (lo, hi) = a.split_at(0, 11);
+        assert_eq!(lo.map(|b| b.volume()), Some(9));
+        assert_eq!(hi.map(|b| b.volume()), Some(18));
+        assert_eq!(a.split_at(1, 5).0, None);
+    }
+}
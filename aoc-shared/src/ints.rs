@@ -0,0 +1,177 @@
+use std::{iter::FusedIterator, marker::PhantomData};
+
+/// Bytes handled at once by the SWAR (SIMD within a register) path.
+const SWAR_WIDTH: usize = 8;
+
+/// True if all 8 little endian bytes of `chunk` are ASCII digits.
+fn swar_all_digits(chunk: u64) -> bool {
+    // every byte is 0x3_, and adding 6 doesn't carry out of the low nibble.
+    chunk & 0xF0F0_F0F0_F0F0_F0F0 == 0x3030_3030_3030_3030
+        && (chunk.wrapping_add(0x0606_0606_0606_0606) & 0xF0F0_F0F0_F0F0_F0F0)
+            == 0x3030_3030_3030_3030
+}
+
+/// Value of 8 ASCII digits, the first digit in the lowest byte.
+fn swar_parse8(chunk: u64) -> u64 {
+    let chunk = chunk - 0x3030_3030_3030_3030;
+    let chunk = (chunk * 10 + (chunk >> 8)) & 0x00FF_00FF_00FF_00FF;
+    let chunk = (chunk * 100 + (chunk >> 16)) & 0x0000_FFFF_0000_FFFF;
+    (chunk * 10000 + (chunk >> 32)) & 0x0000_0000_FFFF_FFFF
+}
+
+fn load8(bytes: &[u8]) -> u64 {
+    u64::from_le_bytes(bytes[..SWAR_WIDTH].try_into().unwrap())
+}
+
+/// Length and value of the run of digits at the start of `bytes`, the value is `None` if it doesn't fit in a u128.
+fn parse_digits(bytes: &[u8]) -> (usize, Option<u128>) {
+    // two SWAR chunks and three more digits, 19 digits always fit in a u64.
+    let mut len = 0;
+    let mut acc = 0u64;
+    while len < 2 * SWAR_WIDTH && len + SWAR_WIDTH <= bytes.len() {
+        let chunk = load8(&bytes[len..]);
+        if !swar_all_digits(chunk) {
+            break;
+        }
+        acc = acc * 100_000_000 + swar_parse8(chunk);
+        len += SWAR_WIDTH;
+    }
+    while len < 19 && len < bytes.len() && bytes[len].is_ascii_digit() {
+        acc = acc * 10 + (bytes[len] - b'0') as u64;
+        len += 1;
+    }
+
+    let mut acc = Some(acc as u128);
+    while len < bytes.len() && bytes[len].is_ascii_digit() {
+        acc = acc
+            .and_then(|acc| acc.checked_mul(10))
+            .and_then(|acc| acc.checked_add((bytes[len] - b'0') as u128));
+        len += 1;
+    }
+    (len, acc)
+}
+
+/// Iterator over the integers in a buffer, see [`try_ints`].
+pub struct TryInts<'a, T> {
+    input: &'a [u8],
+    head: usize,
+    signed: bool,
+    _type: PhantomData<T>,
+}
+
+impl<T> Iterator for TryInts<'_, T>
+where
+    T: TryFrom<u128> + TryFrom<i128>,
+{
+    type Item = Option<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.head
+            + self.input[self.head..]
+                .iter()
+                .position(u8::is_ascii_digit)?;
+        let (len, magnitude) = parse_digits(&self.input[start..]);
+        self.head = start + len;
+        let neg = self.signed
+            && start > 0
+            && self.input[start - 1] == b'-'
+            && !(start > 1 && self.input[start - 2].is_ascii_alphanumeric());
+        Some(magnitude.and_then(|magnitude| {
+            if neg {
+                0i128
+                    .checked_sub_unsigned(magnitude)
+                    .and_then(|num| T::try_from(num).ok())
+            } else {
+                T::try_from(magnitude).ok()
+            }
+        }))
+    }
+}
+
+impl<T> FusedIterator for TryInts<'_, T> where T: TryFrom<u128> + TryFrom<i128> {}
+
+/// Every integer in `input`, skipping anything that isn't a digit.
+/// For signed `T`, a `-` directly before the digits makes them negative, unless it follows a letter or digit, as in `2-4`.
+/// Yields `None` for numbers that don't fit in `T`, like [`crate::try_atoi`].
+pub fn try_ints<T>(input: &[u8]) -> TryInts<'_, T>
+where
+    T: TryFrom<u128> + TryFrom<i128>,
+{
+    TryInts {
+        input,
+        head: 0,
+        signed: T::try_from(-1i128).is_ok(),
+        _type: PhantomData,
+    }
+}
+
+/// Every integer in `input`, see [`try_ints`]. Panics if a number doesn't fit in `T`.
+pub fn ints<T>(input: &[u8]) -> impl Iterator<Item = T> + use<'_, T>
+where
+    T: TryFrom<u128> + TryFrom<i128>,
+{
+    try_ints(input).map(|num| num.expect("integer does not fit in the requested type."))
+}
+
+/// The first `N` integers in `input`, or `None` if there are fewer or one doesn't fit in `T`.
+pub fn ints_array<T, const N: usize>(input: &[u8]) -> Option<[T; N]>
+where
+    T: TryFrom<u128> + TryFrom<i128> + Copy + Default,
+{
+    let mut ret = [T::default(); N];
+    let mut nums = try_ints(input);
+    for slot in ret.iter_mut() {
+        *slot = nums.next()??;
+    }
+    Some(ret)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ints, ints_array, swar_all_digits, swar_parse8, try_ints};
+    use proptest::prelude::*;
+
+    #[test]
+    fn swar() {
+        assert_eq!(swar_parse8(u64::from_le_bytes(*b"12345678")), 12345678);
+        assert!(swar_all_digits(u64::from_le_bytes(*b"09876543")));
+        for junk in [*b"1234567/", *b"1234567:", *b":1234567", *b"12 45678"] {
+            assert!(!swar_all_digits(u64::from_le_bytes(junk)));
+        }
+    }
+
+    #[test]
+    fn extraction() {
+        let input = b"x=-12..3, 2-4 a-5\n1,2,-99";
+        assert_eq!(
+            ints::<i32>(input).collect::<Vec<_>>(),
+            vec![-12, 3, 2, 4, 5, 1, 2, -99]
+        );
+        assert_eq!(
+            ints::<u32>(input).collect::<Vec<_>>(),
+            vec![12, 3, 2, 4, 5, 1, 2, 99]
+        );
+        assert_eq!(ints_array::<u8, 3>(b"1x2y3z4"), Some([1, 2, 3]));
+        assert_eq!(ints_array::<u8, 3>(b"1x2"), None);
+        assert_eq!(ints_array::<u8, 2>(b"1 256"), None);
+        assert_eq!(
+            try_ints::<i64>(b"-9223372036854775808 9223372036854775808").collect::<Vec<_>>(),
+            vec![Some(i64::MIN), None]
+        );
+        assert_eq!(
+            try_ints::<u128>(
+                b"340282366920938463463374607431768211455 340282366920938463463374607431768211456"
+            )
+            .collect::<Vec<_>>(),
+            vec![Some(u128::MAX), None]
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn matches_parse(nums in prop::collection::vec(any::<i64>(), 0..20), sep in "[ ,;\n]{1,3}") {
+            let input = nums.iter().map(|n| n.to_string()).collect::<Vec<_>>().join(&sep);
+            prop_assert_eq!(ints::<i64>(input.as_bytes()).collect::<Vec<_>>(), nums);
+        }
+    }
+}
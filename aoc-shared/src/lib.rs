@@ -23,9 +23,30 @@ mod flat2d;
 pub use flat2d::*;
 mod atoi;
 pub use atoi::*;
+mod ints;
+pub use ints::*;
 mod dijkstra;
 pub use dijkstra::*;
+mod graph;
+pub use graph::*;
+mod simple_path;
+pub use simple_path::*;
+mod cycle;
+pub use cycle::*;
+mod interval;
+pub use interval::*;
+mod point;
+pub use point::*;
+#[cfg(feature = "bigint")]
+pub mod big;
+pub mod geom;
+pub mod hex;
+pub mod ilp;
+pub mod linalg;
+pub mod math;
+pub mod parse;
 pub mod rot;
+pub mod scan;
 
 use std::{
     collections::HashMap,
@@ -0,0 +1,310 @@
+use std::ops::{Index, IndexMut};
+
+use num::{rational::Ratio, FromPrimitive, Integer, Signed, ToPrimitive};
+
+/// Exact rational number backed by i128, fast, but can overflow on large systems.
+pub type Rational = Ratio<i128>;
+/// Exact rational number backed by a big integer, can never overflow.
+pub use num::BigRational;
+
+/// Scalars usable in a `Matrix`; any exact field such as `Rational` or `BigRational`.
+pub trait Field: Clone + PartialEq + Signed + FromPrimitive {}
+
+impl<T> Field for T where T: Clone + PartialEq + Signed + FromPrimitive {}
+
+/// Row-major, dense matrix of exact numbers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Matrix<T> {
+    pub rows: usize,
+    pub cols: usize,
+    data: Vec<T>,
+}
+
+/// Result of solving `A * x = b`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Solution<T> {
+    Unique(Vec<T>),
+    /// Every solution is `particular` plus any linear combination of the `nullspace` basis.
+    Infinite {
+        particular: Vec<T>,
+        nullspace: Vec<Vec<T>>,
+    },
+    Inconsistent,
+}
+
+impl<T: Field> Matrix<T> {
+    /// All zero matrix.
+    pub fn new(rows: usize, cols: usize) -> Self {
+        Matrix {
+            rows,
+            cols,
+            data: vec![T::zero(); rows * cols],
+        }
+    }
+
+    pub fn identity(size: usize) -> Self {
+        let mut ret = Self::new(size, size);
+        (0..size).for_each(|i| ret[(i, i)] = T::one());
+        ret
+    }
+
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Self {
+        let cols = rows.first().map_or(0, |r| r.len());
+        assert!(
+            rows.iter().all(|r| r.len() == cols),
+            "every row must be the same length."
+        );
+        Matrix {
+            rows: rows.len(),
+            cols,
+            data: rows.into_iter().flatten().collect(),
+        }
+    }
+
+    /// Build a matrix out of integer rows.
+    pub fn from_ints<R: AsRef<[i128]>>(rows: &[R]) -> Self {
+        Self::from_rows(
+            rows.iter()
+                .map(|r| r.as_ref().iter().map(|&n| from_int(n)).collect())
+                .collect(),
+        )
+    }
+
+    pub fn row(&self, r: usize) -> &[T] {
+        &self.data[r * self.cols..(r + 1) * self.cols]
+    }
+
+    pub fn mul_vec(&self, x: &[T]) -> Vec<T> {
+        assert_eq!(x.len(), self.cols);
+        (0..self.rows)
+            .map(|r| {
+                self.row(r)
+                    .iter()
+                    .zip(x)
+                    .fold(T::zero(), |acc, (a, b)| acc + a.clone() * b.clone())
+            })
+            .collect()
+    }
+
+    fn swap_rows(&mut self, a: usize, b: usize) {
+        if a != b {
+            (0..self.cols).for_each(|c| self.data.swap(a * self.cols + c, b * self.cols + c));
+        }
+    }
+
+    /// Reduce in place to reduced row echelon form using Gauss-Jordan elimination.
+    /// Returns the pivot column of each non-zero row.
+    pub fn rref(&mut self) -> Vec<usize> {
+        let mut pivots = vec![];
+        let mut row = 0;
+        for col in 0..self.cols {
+            if row == self.rows {
+                break;
+            }
+            let Some(pivot) = (row..self.rows).find(|&r| !self[(r, col)].is_zero()) else {
+                continue;
+            };
+            self.swap_rows(row, pivot);
+            let inv = T::one() / self[(row, col)].clone();
+            (col..self.cols).for_each(|c| self[(row, c)] = self[(row, c)].clone() * inv.clone());
+            for r in (0..self.rows).filter(|&r| r != row) {
+                let factor = self[(r, col)].clone();
+                if factor.is_zero() {
+                    continue;
+                }
+                for c in col..self.cols {
+                    let sub = factor.clone() * self[(row, c)].clone();
+                    self[(r, c)] = self[(r, c)].clone() - sub;
+                }
+            }
+            pivots.push(col);
+            row += 1;
+        }
+        pivots
+    }
+
+    pub fn rank(&self) -> usize {
+        self.clone().rref().len()
+    }
+
+    /// Determinant of a square matrix.
+    pub fn determinant(&self) -> T {
+        assert_eq!(self.rows, self.cols, "determinant needs a square matrix.");
+        let mut m = self.clone();
+        let mut det = T::one();
+        for col in 0..m.cols {
+            let Some(pivot) = (col..m.rows).find(|&r| !m[(r, col)].is_zero()) else {
+                return T::zero();
+            };
+            if pivot != col {
+                m.swap_rows(col, pivot);
+                det = -det;
+            }
+            let p = m[(col, col)].clone();
+            det = det * p.clone();
+            for r in col + 1..m.rows {
+                let factor = m[(r, col)].clone() / p.clone();
+                for c in col..m.cols {
+                    let sub = factor.clone() * m[(col, c)].clone();
+                    m[(r, c)] = m[(r, c)].clone() - sub;
+                }
+            }
+        }
+        det
+    }
+
+    /// Basis of the vectors x where `A * x = 0`.
+    pub fn nullspace(&self) -> Vec<Vec<T>> {
+        let mut m = self.clone();
+        let pivots = m.rref();
+        nullspace_of_rref(&m, &pivots, self.cols)
+    }
+
+    /// Solve `A * x = b`.
+    pub fn solve(&self, b: &[T]) -> Solution<T> {
+        assert_eq!(b.len(), self.rows);
+        let mut aug = Matrix::new(self.rows, self.cols + 1);
+        for r in 0..self.rows {
+            for c in 0..self.cols {
+                aug[(r, c)] = self[(r, c)].clone();
+            }
+            aug[(r, self.cols)] = b[r].clone();
+        }
+        let pivots = aug.rref();
+        if pivots.last() == Some(&self.cols) {
+            return Solution::Inconsistent;
+        }
+        let mut particular = vec![T::zero(); self.cols];
+        pivots
+            .iter()
+            .enumerate()
+            .for_each(|(r, &c)| particular[c] = aug[(r, self.cols)].clone());
+        if pivots.len() == self.cols {
+            Solution::Unique(particular)
+        } else {
+            Solution::Infinite {
+                particular,
+                nullspace: nullspace_of_rref(&aug, &pivots, self.cols),
+            }
+        }
+    }
+}
+
+fn from_int<T: Field>(n: i128) -> T {
+    T::from_i128(n).expect("integer to fit the field type.")
+}
+
+fn nullspace_of_rref<T: Field>(m: &Matrix<T>, pivots: &[usize], cols: usize) -> Vec<Vec<T>> {
+    (0..cols)
+        .filter(|c| !pivots.contains(c))
+        .map(|free| {
+            let mut basis = vec![T::zero(); cols];
+            basis[free] = T::one();
+            pivots
+                .iter()
+                .enumerate()
+                .for_each(|(r, &c)| basis[c] = -m[(r, free)].clone());
+            basis
+        })
+        .collect()
+}
+
+impl<T> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+
+    /// Index by (row, column).
+    fn index(&self, (r, c): (usize, usize)) -> &Self::Output {
+        &self.data[r * self.cols + c]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (r, c): (usize, usize)) -> &mut Self::Output {
+        &mut self.data[r * self.cols + c]
+    }
+}
+
+/// Solve an integer system `A * x = b` exactly.
+/// Returns None unless there is exactly one solution and it is integral.
+pub fn solve_integer_system<R: AsRef<[i128]>>(a: &[R], b: &[i128]) -> Option<Vec<i128>> {
+    let b = b.iter().map(|&n| from_int(n)).collect::<Vec<BigRational>>();
+    match Matrix::<BigRational>::from_ints(a).solve(&b) {
+        Solution::Unique(x) => x
+            .into_iter()
+            .map(|n| ratio_to_int(&n).and_then(|n| n.to_i128()))
+            .collect(),
+        _ => None,
+    }
+}
+
+/// Convert an exact rational to an integer, if it is one.
+pub fn ratio_to_int<T>(n: &Ratio<T>) -> Option<T>
+where
+    T: Clone + Integer,
+{
+    n.is_integer().then(|| n.to_integer())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{solve_integer_system, BigRational, Matrix, Rational, Solution};
+    use num::{One, Zero};
+
+    fn r(n: i128) -> Rational {
+        Rational::from_integer(n)
+    }
+
+    #[test]
+    fn solve_unique() {
+        let a = Matrix::<Rational>::from_ints(&[[2, 1, -1], [-3, -1, 2], [-2, 1, 2]]);
+        assert_eq!(
+            a.solve(&[r(8), r(-11), r(-3)]),
+            Solution::Unique(vec![r(2), r(3), r(-1)])
+        );
+        assert_eq!(a.determinant(), r(-1));
+        assert_eq!(a.rank(), 3);
+    }
+
+    #[test]
+    fn solve_underdetermined() {
+        let a = Matrix::<Rational>::from_ints(&[[1, 2, 3], [2, 4, 6]]);
+        let Solution::Infinite {
+            particular,
+            nullspace,
+        } = a.solve(&[r(6), r(12)])
+        else {
+            panic!("expected infinite solutions");
+        };
+        assert_eq!(a.mul_vec(&particular), vec![r(6), r(12)]);
+        assert_eq!(nullspace.len(), 2);
+        nullspace
+            .iter()
+            .for_each(|v| assert!(a.mul_vec(v).iter().all(Zero::is_zero)));
+        assert_eq!(a.solve(&[r(6), r(11)]), Solution::Inconsistent);
+    }
+
+    #[test]
+    fn solve_fractional() {
+        let a = Matrix::<BigRational>::from_ints(&[[3, 0], [0, 2]]);
+        let Solution::Unique(x) = a.solve(&[BigRational::one(), BigRational::one()]) else {
+            panic!("expected a unique solution");
+        };
+        assert_eq!(
+            x[0].clone() * BigRational::from_integer(3.into()),
+            BigRational::one()
+        );
+        assert_eq!(solve_integer_system(&[[3, 0], [0, 2]], &[1, 1]), None);
+        assert_eq!(
+            solve_integer_system(&[[3, 0], [0, 2]], &[6, -4]),
+            Some(vec![2, -2])
+        );
+    }
+
+    #[test]
+    fn identity_rref() {
+        let mut id = Matrix::<Rational>::identity(4);
+        assert_eq!(id.rref(), vec![0, 1, 2, 3]);
+        assert_eq!(id, Matrix::identity(4));
+        assert!(id.nullspace().is_empty());
+    }
+}
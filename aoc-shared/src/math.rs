@@ -0,0 +1,229 @@
+use num::{Integer, Signed, Unsigned};
+
+/// Extended Euclidean algorithm.
+/// Returns (g, x, y) such that `a * x + b * y = g`, where g is the non-negative gcd of a and b.
+pub fn extended_gcd<T>(a: T, b: T) -> (T, T, T)
+where
+    T: Integer + Signed + Clone,
+{
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (T::one(), T::zero());
+    let (mut old_y, mut y) = (T::zero(), T::one());
+    while !r.is_zero() {
+        let q = old_r.clone() / r.clone();
+        (old_r, r) = (r.clone(), old_r - q.clone() * r);
+        (old_x, x) = (x.clone(), old_x - q.clone() * x);
+        (old_y, y) = (y.clone(), old_y - q * y);
+    }
+    if old_r.is_negative() {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// Modular multiplicative inverse of a modulo m, in the range `0..m`.
+/// None if a and m are not coprime.
+pub fn mod_inverse<T>(a: T, m: T) -> Option<T>
+where
+    T: Integer + Signed + Clone,
+{
+    let (g, x, _) = extended_gcd(a, m.clone());
+    g.is_one().then(|| x.mod_floor(&m))
+}
+
+/// Generalised Chinese Remainder Theorem over (residue, modulus) pairs.
+/// Moduli do not have to be coprime; None is returned if the congruences are inconsistent,
+/// a modulus is not positive, or the lcm of the moduli does not fit in an i64.
+///
+/// Returns (x, lcm of moduli) where x is the smallest non-negative solution.
+pub fn crt<I>(congruences: I) -> Option<(i64, i64)>
+where
+    I: IntoIterator<Item = (i64, i64)>,
+{
+    // one congruence at a time, so that i128 always holds the intermediate products.
+    congruences
+        .into_iter()
+        .try_fold((0, 1), |(r1, m1), (r2, m2)| {
+            let (x, m) = crt_in::<i128, _>([(r1.into(), m1.into()), (r2.into(), m2.into())])?;
+            Some((i64::try_from(x).ok()?, i64::try_from(m).ok()?))
+        })
+}
+
+/// [`crt`] computed in `T`, which has to hold the square of the lcm of the moduli;
+/// use `num::BigInt` when no primitive is wide enough.
+pub fn crt_in<T, I>(congruences: I) -> Option<(T, T)>
+where
+    T: Integer + Signed + Clone,
+    I: IntoIterator<Item = (T, T)>,
+{
+    congruences
+        .into_iter()
+        .try_fold((T::zero(), T::one()), |(r1, m1), (r2, m2)| {
+            if !m2.is_positive() {
+                return None;
+            }
+            let (g, p, _) = extended_gcd(m1.clone(), m2.clone());
+            let diff = r2 - r1.clone();
+            if !(diff.clone() % g.clone()).is_zero() {
+                return None;
+            }
+            let lcm = m1.clone() / g.clone() * m2.clone();
+            let k = (diff / g.clone() * p).mod_floor(&(m2 / g));
+            Some(((r1 + m1 * k).mod_floor(&lcm), lcm))
+        })
+}
+
+/// Least common multiple of every number, e.g. the period of several cycles.
+/// An empty iterator returns 1.
+pub fn lcm_all<T, I>(nums: I) -> T
+where
+    T: Integer,
+    I: IntoIterator<Item = T>,
+{
+    nums.into_iter().fold(T::one(), |acc, n| acc.lcm(&n))
+}
+
+/// Greatest common divisor of every number.
+/// An empty iterator returns 0.
+pub fn gcd_all<T, I>(nums: I) -> T
+where
+    T: Integer,
+    I: IntoIterator<Item = T>,
+{
+    nums.into_iter().fold(T::zero(), |acc, n| acc.gcd(&n))
+}
+
+/// `base^exp % modulus` using u128 intermediates, so no product can overflow.
+/// For a wider modulus use `num::BigUint::modpow`.
+pub fn mod_pow(base: u64, exp: u64, modulus: u64) -> u64 {
+    if modulus == 1 {
+        return 0;
+    }
+    let modulus = modulus as u128;
+    let mut base = base as u128 % modulus;
+    let mut exp = exp;
+    let mut acc = 1u128;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            acc = acc * base % modulus;
+        }
+        base = base * base % modulus;
+        exp >>= 1;
+    }
+    acc as u64
+}
+
+/// Integer square root, the largest r such that `r * r <= n`.
+pub fn isqrt<T>(n: T) -> T
+where
+    T: Integer + Unsigned + Clone,
+{
+    let two = T::one() + T::one();
+    if n < two {
+        return n;
+    }
+    // Newton's method, starting above the root so that it converges from above.
+    let mut x = n.clone() / two.clone() + T::one();
+    let mut y = (x.clone() + n.clone() / x.clone()) / two.clone();
+    while y < x {
+        x = y;
+        y = (x.clone() + n.clone() / x.clone()) / two.clone();
+    }
+    x
+}
+
+#[cfg(test)]
+mod test {
+    use super::{crt, crt_in, extended_gcd, gcd_all, isqrt, lcm_all, mod_inverse, mod_pow};
+    use num::{BigInt, BigUint, Integer};
+    use proptest::prelude::*;
+
+    #[test]
+    fn crt_examples() {
+        assert_eq!(crt([(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        // non-coprime, but consistent.
+        assert_eq!(crt([(2, 4), (4, 6)]), Some((10, 12)));
+        // non-coprime, inconsistent.
+        assert_eq!(crt([(1, 4), (2, 6)]), None);
+        assert_eq!(crt([]), Some((0, 1)));
+        // bad moduli, and an lcm past i64.
+        assert_eq!(crt([(1, 3), (0, 0)]), None);
+        assert_eq!(crt([(1, -3)]), None);
+        let big = (1 << 62) - 57;
+        assert_eq!(crt([(1, big), (0, 3)]), None);
+    }
+
+    #[test]
+    fn lcm_of_cycles() {
+        assert_eq!(lcm_all([4u64, 6, 10]), 60);
+        assert_eq!(lcm_all(Vec::<u64>::new()), 1);
+        assert_eq!(gcd_all([12i64, -18, 30]), 6);
+    }
+
+    #[test]
+    fn big() {
+        // coprime moduli whose lcm, and its square, overflow i128.
+        let moduli = [u64::MAX - 58, u64::MAX - 82, u64::MAX - 94].map(BigInt::from);
+        let x = BigInt::from(u128::MAX) * 12345;
+        let lcm = moduli.iter().product::<BigInt>();
+        assert_eq!(
+            crt_in(moduli.iter().map(|m| (&x % m, m.clone()))),
+            Some((x, lcm.clone()))
+        );
+        assert_eq!(lcm_all(moduli.clone()), lcm);
+        assert_eq!(
+            mod_inverse(BigInt::from(3), moduli[0].clone()).map(|inv| inv * 3 % &moduli[0]),
+            Some(BigInt::from(1))
+        );
+        let root = BigUint::from(u128::MAX) + 2u32;
+        assert_eq!(isqrt(root.clone() * &root + 1u32), root);
+    }
+
+    proptest! {
+        #[test]
+        fn extended_gcd_bezout(a in -1_000_000i64..1_000_000, b in -1_000_000i64..1_000_000) {
+            let (g, x, y) = extended_gcd(a, b);
+            prop_assert_eq!(a * x + b * y, g);
+            prop_assert_eq!(g, a.gcd(&b));
+        }
+
+        #[test]
+        fn mod_inverse_is_inverse(a in -1_000_000i64..1_000_000, m in 2i64..1_000_000) {
+            match mod_inverse(a, m) {
+                Some(inv) => {
+                    prop_assert!((0..m).contains(&inv));
+                    prop_assert_eq!((a * inv).rem_euclid(m), 1);
+                }
+                None => prop_assert_ne!(a.gcd(&m), 1),
+            }
+        }
+
+        #[test]
+        fn crt_solves_all(x in 0i64..1_000_000_000, moduli in prop::collection::vec(1i64..1000, 1..6)) {
+            let (sol, lcm) = crt(moduli.iter().map(|&m| (x % m, m))).expect("consistent by construction");
+            prop_assert_eq!(lcm, lcm_all(moduli.iter().copied()));
+            prop_assert_eq!(sol, x % lcm);
+        }
+
+        #[test]
+        fn mod_pow_matches_naive(base in any::<u64>(), exp in 0u64..64, modulus in 1u64..u64::MAX) {
+            let naive = (0..exp).fold(1u128 % modulus as u128, |acc, _| acc * (base as u128) % modulus as u128);
+            prop_assert_eq!(mod_pow(base, exp, modulus) as u128, naive);
+        }
+
+        #[test]
+        fn isqrt_is_floor(n in any::<u64>()) {
+            let r = isqrt(n) as u128;
+            prop_assert!(r * r <= n as u128);
+            prop_assert!((r + 1) * (r + 1) > n as u128);
+        }
+
+        #[test]
+        fn isqrt_u128(n in any::<u128>()) {
+            let r = isqrt(n);
+            prop_assert!(r.checked_mul(r).is_some_and(|sq| sq <= n));
+            prop_assert!((r + 1).checked_mul(r + 1).is_none_or(|sq| sq > n));
+        }
+    }
+}
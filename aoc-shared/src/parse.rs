@@ -0,0 +1,431 @@
+//! Parser combinators over the [`AoCTokenizer`] token stream.
+//!
+//! Spaces are skipped between tokens, newlines are not.
+//! A parser that fails without consuming anything lets [`optional`], [`many`] and [`sep_by`] backtrack;
+//! once it has consumed a token, its error is reported as is.
+//!
+//! # Examples
+//!
+//! ```
+//! use aoc_shared::parse::{expect, line, many, number, parse_all, sep_by, Cursor, ParseResult, Parser};
+//!
+//! fn hail(c: &mut Cursor) -> ParseResult<(Vec<i64>, Vec<i64>)> {
+//!     let pos = sep_by(number(), expect(",")).parse(c)?;
+//!     expect("@").parse(c)?;
+//!     let vel = sep_by(number(), expect(",")).parse(c)?;
+//!     Ok((pos, vel))
+//! }
+//!
+//! let input = b"19, 13, 30 @ -2, 1, -2\n18, 19, 22 @ -1, -1, -2\n";
+//! let stones = parse_all(input, many(line(hail))).unwrap();
+//! assert_eq!(stones[1], (vec![18, 19, 22], vec![-1, -1, -2]));
+//!
+//! let err = parse_all(b"19, 13, 30 @ -2, x", many(line(hail))).unwrap_err();
+//! assert_eq!((err.line, err.col), (1, 18));
+//! assert_eq!(err.to_string(), "1:18: expected a number, found `x`");
+//! ```
+
+use crate::{AoCTokenizer, Token};
+use std::fmt;
+
+pub type ParseResult<T> = Result<T, ParseError>;
+
+/// Where and why parsing failed; `line` and `col` are 1-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub offset: usize,
+    pub line: usize,
+    pub col: usize,
+    pub expected: String,
+    pub found: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}: expected {}, found {}",
+            self.line, self.col, self.expected, self.found
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Position in the token stream; cheap to clone for backtracking.
+#[derive(Clone)]
+pub struct Cursor<'a> {
+    input: &'a [u8],
+    tokens: AoCTokenizer<'a>,
+}
+
+impl<'a> Cursor<'a> {
+    pub fn new(input: &'a [u8]) -> Self {
+        Cursor {
+            input,
+            tokens: AoCTokenizer::new(input),
+        }
+    }
+
+    fn skip_spaces(&mut self) {
+        let mut ahead = self.tokens.clone();
+        while let Some(Token::Space) = ahead.next() {
+            self.tokens = ahead.clone();
+        }
+    }
+
+    /// Byte offset of the next token.
+    pub fn offset(&self) -> usize {
+        let mut this = self.clone();
+        this.skip_spaces();
+        this.tokens.offset()
+    }
+
+    /// The next token, without consuming it.
+    pub fn peek(&self) -> Token<'a> {
+        self.clone().next_token().1
+    }
+
+    /// Consume the next token, returning it with its byte offset.
+    pub fn next_token(&mut self) -> (usize, Token<'a>) {
+        self.skip_spaces();
+        let offset = self.tokens.offset();
+        (offset, self.tokens.next().unwrap_or(Token::End))
+    }
+
+    /// An error for the token at `offset`.
+    pub fn error_at(&self, offset: usize, expected: impl Into<String>) -> ParseError {
+        let before = &self.input[..offset.min(self.input.len())];
+        let line_start = before
+            .iter()
+            .rposition(|&c| c == b'\n')
+            .map_or(0, |i| i + 1);
+        let found = AoCTokenizer::new(&self.input[offset.min(self.input.len())..])
+            .next()
+            .map_or_else(|| "end of input".to_owned(), |token| describe(&token));
+        ParseError {
+            offset,
+            line: before.iter().filter(|&&c| c == b'\n').count() + 1,
+            col: offset - line_start + 1,
+            expected: expected.into(),
+            found,
+        }
+    }
+
+    /// An error for the next token.
+    pub fn error(&self, expected: impl Into<String>) -> ParseError {
+        self.error_at(self.offset(), expected)
+    }
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Number(num) => format!("`{num}`"),
+        Token::Something(word) | Token::Word(word) => {
+            format!("`{}`", String::from_utf8_lossy(word))
+        }
+        Token::Delimiter(delim) => format!("`{}`", *delim as char),
+        Token::Newline => "newline".to_owned(),
+        Token::DoubleNewline => "blank line".to_owned(),
+        Token::Space => "space".to_owned(),
+        Token::End => "end of input".to_owned(),
+    }
+}
+
+pub trait Parser<'a, T> {
+    fn parse(&self, cursor: &mut Cursor<'a>) -> ParseResult<T>;
+
+    fn map<U, F>(self, f: F) -> impl Parser<'a, U>
+    where
+        Self: Sized,
+        F: Fn(T) -> U,
+    {
+        move |cursor: &mut Cursor<'a>| self.parse(cursor).map(&f)
+    }
+}
+
+impl<'a, T, F> Parser<'a, T> for F
+where
+    F: Fn(&mut Cursor<'a>) -> ParseResult<T>,
+{
+    fn parse(&self, cursor: &mut Cursor<'a>) -> ParseResult<T> {
+        self(cursor)
+    }
+}
+
+/// Run `parser` over the whole input; trailing newlines are ignored.
+pub fn parse_all<'a, T>(input: &'a [u8], parser: impl Parser<'a, T>) -> ParseResult<T> {
+    let mut cursor = Cursor::new(input);
+    let ret = parser.parse(&mut cursor)?;
+    loop {
+        match cursor.next_token() {
+            (_, Token::Newline | Token::DoubleNewline) => (),
+            (_, Token::End) => return Ok(ret),
+            (offset, _) => return Err(cursor.error_at(offset, "end of input")),
+        }
+    }
+}
+
+/// Any word.
+pub fn word<'a>() -> impl Parser<'a, &'a [u8]> {
+    |cursor: &mut Cursor<'a>| match cursor.next_token() {
+        (_, Token::Something(word)) => Ok(word),
+        (offset, _) => Err(cursor.error_at(offset, "a word")),
+    }
+}
+
+/// Exactly the word `expected`.
+pub fn expect_word<'a>(expected: &'static str) -> impl Parser<'a, ()> {
+    move |cursor: &mut Cursor<'a>| match cursor.next_token() {
+        (_, Token::Something(word)) if word == expected.as_bytes() => Ok(()),
+        (offset, _) => Err(cursor.error_at(offset, format!("`{expected}`"))),
+    }
+}
+
+/// The tokens of `pattern`, e.g. `"Test: divisible by"`; spaces in the pattern are ignored.
+pub fn expect<'a>(pattern: &'static str) -> impl Parser<'a, ()> {
+    move |cursor: &mut Cursor<'a>| {
+        for expected in AoCTokenizer::new(pattern.as_bytes()) {
+            match expected {
+                Token::Space | Token::End => continue,
+                _ => (),
+            }
+            match cursor.next_token() {
+                (_, token) if token == expected => (),
+                (offset, _) => return Err(cursor.error_at(offset, describe(&expected))),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// One of the delimiters in `delims`, returning which.
+pub fn one_of<'a>(delims: &'static [u8]) -> impl Parser<'a, u8> {
+    move |cursor: &mut Cursor<'a>| match cursor.next_token() {
+        (_, Token::Delimiter(delim)) if delims.contains(&delim) => Ok(delim),
+        (offset, _) => Err(cursor.error_at(
+            offset,
+            format!("one of `{}`", String::from_utf8_lossy(delims)),
+        )),
+    }
+}
+
+/// A decimal integer with an optional leading `-` or `+`.
+pub fn number<'a, T: TryFrom<i128>>() -> impl Parser<'a, T> {
+    |cursor: &mut Cursor<'a>| {
+        let (start, mut token) = cursor.next_token();
+        let neg = match token {
+            Token::Delimiter(sign @ (b'-' | b'+')) => {
+                token = cursor.tokens.next().unwrap_or(Token::End);
+                sign == b'-'
+            }
+            _ => false,
+        };
+        let digits = match token {
+            Token::Something(digits) if digits.iter().all(u8::is_ascii_digit) => digits,
+            _ => return Err(cursor.error_at(start, "a number")),
+        };
+        digits
+            .iter()
+            .try_fold(0i128, |acc, &digit| {
+                let digit = (digit - b'0') as i128;
+                acc.checked_mul(10)?
+                    .checked_add(if neg { -digit } else { digit })
+            })
+            .and_then(|num| T::try_from(num).ok())
+            .ok_or_else(|| {
+                cursor.error_at(
+                    start,
+                    format!("a number that fits in {}", std::any::type_name::<T>()),
+                )
+            })
+    }
+}
+
+/// Backtrack if `parser` fails without consuming anything.
+fn attempt<'a, T>(parser: &impl Parser<'a, T>, cursor: &mut Cursor<'a>) -> ParseResult<Option<T>> {
+    let start = cursor.offset();
+    let mut ahead = cursor.clone();
+    match parser.parse(&mut ahead) {
+        Ok(ret) => {
+            *cursor = ahead;
+            Ok(Some(ret))
+        }
+        Err(err) if err.offset == start => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+/// `parser`, or `None` if it fails on its first token.
+pub fn optional<'a, T>(parser: impl Parser<'a, T>) -> impl Parser<'a, Option<T>> {
+    move |cursor: &mut Cursor<'a>| attempt(&parser, cursor)
+}
+
+/// Zero or more of `parser`.
+pub fn many<'a, T>(parser: impl Parser<'a, T>) -> impl Parser<'a, Vec<T>> {
+    move |cursor: &mut Cursor<'a>| {
+        let mut ret = vec![];
+        loop {
+            let start = cursor.offset();
+            match attempt(&parser, cursor)? {
+                Some(item) => ret.push(item),
+                None => return Ok(ret),
+            }
+            // no progress, stop before looping forever.
+            if cursor.offset() == start {
+                return Ok(ret);
+            }
+        }
+    }
+}
+
+/// Zero or more of `item`, separated by `sep`.
+pub fn sep_by<'a, T, S>(
+    item: impl Parser<'a, T>,
+    sep: impl Parser<'a, S>,
+) -> impl Parser<'a, Vec<T>> {
+    move |cursor: &mut Cursor<'a>| {
+        let mut ret = vec![];
+        if let Some(first) = attempt(&item, cursor)? {
+            ret.push(first);
+            while attempt(&sep, cursor)?.is_some() {
+                ret.push(item.parse(cursor)?);
+            }
+        }
+        Ok(ret)
+    }
+}
+
+/// `prefix` then `parser`, keeping the latter.
+pub fn preceded<'a, P, T>(
+    prefix: impl Parser<'a, P>,
+    parser: impl Parser<'a, T>,
+) -> impl Parser<'a, T> {
+    move |cursor: &mut Cursor<'a>| {
+        prefix.parse(cursor)?;
+        parser.parse(cursor)
+    }
+}
+
+/// `parser` then `suffix`, keeping the former.
+pub fn terminated<'a, T, S>(
+    parser: impl Parser<'a, T>,
+    suffix: impl Parser<'a, S>,
+) -> impl Parser<'a, T> {
+    move |cursor: &mut Cursor<'a>| {
+        let ret = parser.parse(cursor)?;
+        suffix.parse(cursor)?;
+        Ok(ret)
+    }
+}
+
+/// `parser` followed by the end of the line.
+/// A blank line or the end of input are left for an enclosing [`record`] or [`parse_all`].
+pub fn line<'a, T>(parser: impl Parser<'a, T>) -> impl Parser<'a, T> {
+    move |cursor: &mut Cursor<'a>| {
+        let ret = parser.parse(cursor)?;
+        match cursor.peek() {
+            Token::Newline => {
+                cursor.next_token();
+                Ok(ret)
+            }
+            Token::DoubleNewline | Token::End => Ok(ret),
+            _ => Err(cursor.error("end of line")),
+        }
+    }
+}
+
+/// `parser` followed by a blank line or the end of input, like the records of [`crate::RecordGrouper`].
+pub fn record<'a, T>(parser: impl Parser<'a, T>) -> impl Parser<'a, T> {
+    move |cursor: &mut Cursor<'a>| {
+        let ret = parser.parse(cursor)?;
+        let mut ahead = cursor.clone();
+        if let (_, Token::Newline) = ahead.next_token() {
+            *cursor = ahead;
+        }
+        match cursor.peek() {
+            Token::DoubleNewline => {
+                cursor.next_token();
+                Ok(ret)
+            }
+            Token::End => Ok(ret),
+            _ => Err(cursor.error("end of record")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        expect, expect_word, line, many, number, one_of, optional, parse_all, preceded, record,
+        sep_by, terminated, word, Cursor, Parser,
+    };
+
+    #[test]
+    fn numbers() {
+        assert_eq!(parse_all(b"-42", number::<i32>()), Ok(-42));
+        assert_eq!(parse_all(b"+7\n\n", number::<u8>()), Ok(7));
+        assert_eq!(parse_all(b"128", number::<i8>()).unwrap_err().col, 1);
+        assert!(parse_all(b"-1", number::<u64>()).is_err());
+        assert!(parse_all(b"12ab", number::<u64>()).is_err());
+        assert_eq!(
+            parse_all(
+                b"-170141183460469231731687303715884105728",
+                number::<i128>()
+            ),
+            Ok(i128::MIN)
+        );
+    }
+
+    #[test]
+    fn backtracking() {
+        let list = |c: &mut Cursor| {
+            expect("[").parse(c)?;
+            let ret = sep_by(number::<u32>(), expect(",")).parse(c)?;
+            expect("]").parse(c)?;
+            Ok(ret)
+        };
+        assert_eq!(parse_all(b"[]", list), Ok(vec![]));
+        assert_eq!(parse_all(b"[1, 2,3]", list), Ok(vec![1, 2, 3]));
+        let err = parse_all(b"[1,\n2]", list).unwrap_err();
+        assert_eq!((err.line, err.col, err.found.as_str()), (1, 4, "newline"));
+        // optional only backtracks when nothing was consumed.
+        let assign = || terminated(optional(preceded(expect("x ="), number::<i32>())), word());
+        assert_eq!(parse_all(b"y", assign()), Ok(None));
+        assert_eq!(parse_all(b"x = 3 y", assign()), Ok(Some(3)));
+        assert_eq!(parse_all(b"x y", assign()).unwrap_err().col, 3);
+    }
+
+    #[test]
+    fn records() {
+        const MONKEYS: &[u8] = b"Monkey 0:
+  Operation: new = old * 19
+  Test: divisible by 23
+
+Monkey 1:
+  Operation: new = old + old
+  Test: divisible by 19
+";
+        let monkey = |c: &mut Cursor| {
+            let id = preceded(expect_word("Monkey"), number::<usize>()).parse(c)?;
+            line(expect(":")).parse(c)?;
+            let op = preceded(expect("Operation: new = old"), one_of(b"*+")).parse(c)?;
+            let rhs = line(|c: &mut Cursor| {
+                let rhs = optional(number::<u64>()).parse(c)?;
+                if rhs.is_none() {
+                    expect_word("old").parse(c)?;
+                }
+                Ok(rhs)
+            })
+            .parse(c)?;
+            let div = line(preceded(expect("Test: divisible by"), number::<u64>())).parse(c)?;
+            Ok((id, op, rhs, div))
+        };
+        assert_eq!(
+            parse_all(MONKEYS, many(record(monkey))),
+            Ok(vec![(0, b'*', Some(19), 23), (1, b'+', None, 19)])
+        );
+        let err = parse_all(b"Monkey 0: x\n", many(record(monkey))).unwrap_err();
+        assert_eq!(err.to_string(), "1:11: expected end of line, found `x`");
+    }
+}
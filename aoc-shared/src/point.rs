@@ -0,0 +1,416 @@
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub, SubAssign};
+
+use num::{NumCast, PrimInt, Signed};
+
+use crate::FlatVec2D;
+
+/// 2D point or vector; y grows downwards like the rows of a puzzle input.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T> Point2<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Point2 { x, y }
+    }
+}
+
+impl<T: PrimInt> Point2<T> {
+    pub fn manhattan(self, other: Self) -> T {
+        abs_diff(self.x, other.x) + abs_diff(self.y, other.y)
+    }
+
+    /// Distance when diagonal moves cost the same as straight ones.
+    pub fn chebyshev(self, other: Self) -> T {
+        abs_diff(self.x, other.x).max(abs_diff(self.y, other.y))
+    }
+
+    /// Convert to another integer type, None if a coordinate does not fit, e.g. is negative for usize.
+    pub fn cast<U: NumCast>(self) -> Option<Point2<U>> {
+        Some(Point2::new(U::from(self.x)?, U::from(self.y)?))
+    }
+}
+
+impl<T: Signed + Copy> Point2<T> {
+    /// Rotate around the origin, clockwise.
+    pub fn rot_right(self) -> Self {
+        Point2::new(-self.y, self.x)
+    }
+
+    /// Rotate around the origin, counter-clockwise.
+    pub fn rot_left(self) -> Self {
+        Point2::new(self.y, -self.x)
+    }
+
+    pub fn rot_180(self) -> Self {
+        -self
+    }
+}
+
+fn abs_diff<T: PrimInt>(a: T, b: T) -> T {
+    if a > b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+impl<T> From<(T, T)> for Point2<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Point2::new(x, y)
+    }
+}
+
+impl<T> From<Point2<T>> for (T, T) {
+    fn from(p: Point2<T>) -> Self {
+        (p.x, p.y)
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point2<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Point2::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point2<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Point2::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point2<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Point2::new(-self.x, -self.y)
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Point2<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Point2::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl<T: AddAssign> AddAssign for Point2<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+    }
+}
+
+impl<T: SubAssign> SubAssign for Point2<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+    }
+}
+
+impl Add<Dir4> for Point2<isize> {
+    type Output = Self;
+
+    fn add(self, rhs: Dir4) -> Self::Output {
+        self + rhs.delta()
+    }
+}
+
+impl AddAssign<Dir4> for Point2<isize> {
+    fn add_assign(&mut self, rhs: Dir4) {
+        *self += rhs.delta();
+    }
+}
+
+impl Add<Dir8> for Point2<isize> {
+    type Output = Self;
+
+    fn add(self, rhs: Dir8) -> Self::Output {
+        self + rhs.delta()
+    }
+}
+
+/// Cardinal direction; in the same order as `rot::CARDINALS`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Dir4 {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Dir4 {
+    pub const ALL: [Dir4; 4] = [Dir4::North, Dir4::East, Dir4::South, Dir4::West];
+
+    /// Unit vector of one step in this direction.
+    pub const fn delta(self) -> Point2<isize> {
+        match self {
+            Dir4::North => Point2::new(0, -1),
+            Dir4::East => Point2::new(1, 0),
+            Dir4::South => Point2::new(0, 1),
+            Dir4::West => Point2::new(-1, 0),
+        }
+    }
+
+    /// Index into `ALL` or `rot::CARDINALS`.
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+
+    pub const fn turn_right(self) -> Self {
+        Dir4::ALL[(self as usize + 1) % 4]
+    }
+
+    pub const fn turn_left(self) -> Self {
+        Dir4::ALL[(self as usize + 3) % 4]
+    }
+
+    pub const fn opposite(self) -> Self {
+        Dir4::ALL[(self as usize + 2) % 4]
+    }
+
+    pub const fn is_vertical(self) -> bool {
+        matches!(self, Dir4::North | Dir4::South)
+    }
+
+    /// Parse arrows `^>v<`, compass letters `NESW` or `UDLR`.
+    pub const fn from_byte(c: u8) -> Option<Self> {
+        match c {
+            b'^' | b'N' | b'U' => Some(Dir4::North),
+            b'>' | b'E' | b'R' => Some(Dir4::East),
+            b'v' | b'S' | b'D' => Some(Dir4::South),
+            b'<' | b'W' | b'L' => Some(Dir4::West),
+            _ => None,
+        }
+    }
+
+    /// One of `^>v<`.
+    pub const fn arrow(self) -> char {
+        match self {
+            Dir4::North => '^',
+            Dir4::East => '>',
+            Dir4::South => 'v',
+            Dir4::West => '<',
+        }
+    }
+
+    /// One of `NESW`.
+    pub const fn letter(self) -> char {
+        match self {
+            Dir4::North => 'N',
+            Dir4::East => 'E',
+            Dir4::South => 'S',
+            Dir4::West => 'W',
+        }
+    }
+}
+
+impl TryFrom<u8> for Dir4 {
+    type Error = u8;
+
+    /// see: `Dir4::from_byte`
+    fn try_from(c: u8) -> Result<Self, Self::Error> {
+        Dir4::from_byte(c).ok_or(c)
+    }
+}
+
+impl TryFrom<char> for Dir4 {
+    type Error = char;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        u8::try_from(c).ok().and_then(Dir4::from_byte).ok_or(c)
+    }
+}
+
+impl From<Dir4> for char {
+    fn from(dir: Dir4) -> Self {
+        dir.arrow()
+    }
+}
+
+/// Cardinal and intercardinal direction, clockwise from North.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Dir8 {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Dir8 {
+    pub const ALL: [Dir8; 8] = [
+        Dir8::North,
+        Dir8::NorthEast,
+        Dir8::East,
+        Dir8::SouthEast,
+        Dir8::South,
+        Dir8::SouthWest,
+        Dir8::West,
+        Dir8::NorthWest,
+    ];
+
+    pub const fn delta(self) -> Point2<isize> {
+        match self {
+            Dir8::North => Point2::new(0, -1),
+            Dir8::NorthEast => Point2::new(1, -1),
+            Dir8::East => Point2::new(1, 0),
+            Dir8::SouthEast => Point2::new(1, 1),
+            Dir8::South => Point2::new(0, 1),
+            Dir8::SouthWest => Point2::new(-1, 1),
+            Dir8::West => Point2::new(-1, 0),
+            Dir8::NorthWest => Point2::new(-1, -1),
+        }
+    }
+
+    /// Turn 45 degrees clockwise.
+    pub const fn turn_right(self) -> Self {
+        Dir8::ALL[(self as usize + 1) % 8]
+    }
+
+    /// Turn 45 degrees counter-clockwise.
+    pub const fn turn_left(self) -> Self {
+        Dir8::ALL[(self as usize + 7) % 8]
+    }
+
+    pub const fn opposite(self) -> Self {
+        Dir8::ALL[(self as usize + 4) % 8]
+    }
+
+    pub const fn is_diagonal(self) -> bool {
+        self as usize % 2 == 1
+    }
+}
+
+impl From<Dir4> for Dir8 {
+    fn from(dir: Dir4) -> Self {
+        Dir8::ALL[dir as usize * 2]
+    }
+}
+
+impl<T> FlatVec2D<T> {
+    /// Bounds checked point, allowing for user calculations that could be negative.
+    pub fn get_point(&self, p: Point2<isize>) -> Option<&T> {
+        self.get_isize((p.x, p.y))
+    }
+
+    pub fn get_point_mut(&mut self, p: Point2<isize>) -> Option<&mut T> {
+        self.get_isize_mut((p.x, p.y))
+    }
+
+    /// Step once from a point, None if that leaves the grid.
+    pub fn step(&self, p: Point2<usize>, dir: Dir4) -> Option<Point2<usize>> {
+        let next = p.cast::<isize>()? + dir;
+        self.to_bounds(next.x, next.y).map(Point2::from)
+    }
+
+    /// In bounds cardinal neighbours of a point, with the direction taken to reach them.
+    pub fn neighbours4(
+        &self,
+        p: Point2<usize>,
+    ) -> impl Iterator<Item = (Dir4, Point2<usize>)> + '_ {
+        Dir4::ALL
+            .into_iter()
+            .filter_map(move |dir| self.step(p, dir).map(|next| (dir, next)))
+    }
+
+    /// In bounds neighbours of a point including diagonals, with the direction taken to reach them.
+    pub fn neighbours8(
+        &self,
+        p: Point2<usize>,
+    ) -> impl Iterator<Item = (Dir8, Point2<usize>)> + '_ {
+        Dir8::ALL.into_iter().filter_map(move |dir| {
+            let next = p.cast::<isize>()? + dir;
+            self.to_bounds(next.x, next.y)
+                .map(|next| (dir, Point2::from(next)))
+        })
+    }
+
+    /// Every point of the grid, row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point2<usize>> + '_ {
+        self.xyrange().map(Point2::from)
+    }
+}
+
+impl<T> Index<Point2<usize>> for FlatVec2D<T> {
+    type Output = T;
+
+    fn index(&self, p: Point2<usize>) -> &Self::Output {
+        &self[(p.x, p.y)]
+    }
+}
+
+impl<T> IndexMut<Point2<usize>> for FlatVec2D<T> {
+    fn index_mut(&mut self, p: Point2<usize>) -> &mut Self::Output {
+        &mut self[(p.x, p.y)]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Dir4, Dir8, Point2};
+    use crate::{parse_to_flat2d, rot, FlatVec2D};
+
+    #[test]
+    fn dirs() {
+        for (dir, card) in Dir4::ALL.iter().zip(rot::CARDINALS) {
+            assert_eq!(dir.delta(), Point2::from(card));
+            assert_eq!(dir.turn_right().delta(), dir.delta().rot_right());
+            assert_eq!(dir.turn_left().turn_right(), *dir);
+            assert_eq!(dir.opposite().delta(), -dir.delta());
+            assert_eq!(Dir4::try_from(dir.arrow()), Ok(*dir));
+            assert_eq!(Dir4::try_from(dir.letter()), Ok(*dir));
+            assert_eq!(Dir8::from(*dir).delta(), dir.delta());
+        }
+        assert_eq!(Dir4::try_from(b'x'), Err(b'x'));
+        assert_eq!(Dir4::try_from('U'), Ok(Dir4::North));
+        assert_eq!(Dir8::NorthWest.turn_right(), Dir8::North);
+        assert_eq!(Dir8::SouthEast.opposite(), Dir8::NorthWest);
+        assert!(Dir8::SouthWest.is_diagonal());
+    }
+
+    #[test]
+    fn points() {
+        let a = Point2::new(3isize, -4);
+        let b = Point2::new(-1, 2);
+        assert_eq!(a + b, Point2::new(2, -2));
+        assert_eq!(a - b, Point2::new(4, -6));
+        assert_eq!(a * 2, Point2::new(6, -8));
+        assert_eq!(a.manhattan(b), 10);
+        assert_eq!(a.chebyshev(b), 6);
+        assert_eq!(Point2::new(2usize, 9).manhattan(Point2::new(5, 1)), 11);
+        assert_eq!(a.cast::<usize>(), None);
+        assert_eq!(a.rot_right().rot_left(), a);
+        assert_eq!(a.rot_180(), -a);
+        assert_eq!(a + Dir4::North, Point2::new(3, -5));
+    }
+
+    #[test]
+    fn grid_neighbours() {
+        let grid: FlatVec2D<u8> = parse_to_flat2d(b"abc\ndef\n");
+        let corner = Point2::new(0, 0);
+        assert_eq!(grid[Point2::new(1usize, 1)], b'e');
+        assert_eq!(
+            grid.neighbours4(corner).collect::<Vec<_>>(),
+            vec![
+                (Dir4::East, Point2::new(1, 0)),
+                (Dir4::South, Point2::new(0, 1))
+            ]
+        );
+        assert_eq!(grid.neighbours8(Point2::new(1, 0)).count(), 5);
+        assert_eq!(grid.step(corner, Dir4::West), None);
+        assert_eq!(grid.get_point(Point2::new(2, 1)), Some(&b'f'));
+        assert_eq!(grid.get_point(Point2::new(3, 1)), None);
+        assert_eq!(grid.points().count(), 6);
+    }
+}
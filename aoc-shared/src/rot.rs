@@ -1,3 +1,8 @@
+use std::{
+    collections::HashMap,
+    ops::{Add, Mul, Neg, Sub},
+};
+
 type Point = (isize, isize);
 
 /// North-bound movement vector
@@ -52,3 +57,279 @@ pub const fn rot_180(xy: Point) -> Point {
 pub const fn rot_left(xy: Point) -> Point {
     rot_right(rot_right(rot_right(xy)))
 }
+
+/// Integer 3D vector, e.g. a beacon position relative to a scanner.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Vec3 {
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
+}
+
+impl Vec3 {
+    pub const fn new(x: i64, y: i64, z: i64) -> Self {
+        Vec3 { x, y, z }
+    }
+
+    pub const fn to_array(self) -> [i64; 3] {
+        [self.x, self.y, self.z]
+    }
+
+    pub const fn manhattan(self, other: Vec3) -> u64 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y) + self.z.abs_diff(other.z)
+    }
+}
+
+impl From<(i64, i64, i64)> for Vec3 {
+    fn from((x, y, z): (i64, i64, i64)) -> Self {
+        Vec3::new(x, y, z)
+    }
+}
+
+impl From<[i64; 3]> for Vec3 {
+    fn from([x, y, z]: [i64; 3]) -> Self {
+        Vec3::new(x, y, z)
+    }
+}
+
+impl Add for Vec3 {
+    type Output = Vec3;
+
+    fn add(self, rhs: Vec3) -> Self::Output {
+        Vec3::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl Sub for Vec3 {
+    type Output = Vec3;
+
+    fn sub(self, rhs: Vec3) -> Self::Output {
+        Vec3::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl Neg for Vec3 {
+    type Output = Vec3;
+
+    fn neg(self) -> Self::Output {
+        Vec3::new(-self.x, -self.y, -self.z)
+    }
+}
+
+/// Proper rotation of the cube onto itself, stored as a signed permutation matrix with determinant 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rot3([[i64; 3]; 3]);
+
+impl Rot3 {
+    pub const IDENTITY: Rot3 = Rot3([[1, 0, 0], [0, 1, 0], [0, 0, 1]]);
+
+    pub const fn matrix(&self) -> [[i64; 3]; 3] {
+        self.0
+    }
+
+    pub const fn apply(&self, v: Vec3) -> Vec3 {
+        let m = &self.0;
+        Vec3::new(
+            m[0][0] * v.x + m[0][1] * v.y + m[0][2] * v.z,
+            m[1][0] * v.x + m[1][1] * v.y + m[1][2] * v.z,
+            m[2][0] * v.x + m[2][1] * v.y + m[2][2] * v.z,
+        )
+    }
+
+    /// Rotation applying `rhs` first, then self.
+    pub const fn compose(&self, rhs: &Rot3) -> Rot3 {
+        let mut m = [[0; 3]; 3];
+        let mut r = 0;
+        while r < 3 {
+            let mut c = 0;
+            while c < 3 {
+                m[r][c] = self.0[r][0] * rhs.0[0][c]
+                    + self.0[r][1] * rhs.0[1][c]
+                    + self.0[r][2] * rhs.0[2][c];
+                c += 1;
+            }
+            r += 1;
+        }
+        Rot3(m)
+    }
+
+    /// Rotation undoing self; the transpose, since rotation matrices are orthogonal.
+    pub const fn inverse(&self) -> Rot3 {
+        let m = &self.0;
+        Rot3([
+            [m[0][0], m[1][0], m[2][0]],
+            [m[0][1], m[1][1], m[2][1]],
+            [m[0][2], m[1][2], m[2][2]],
+        ])
+    }
+}
+
+impl Mul<Vec3> for Rot3 {
+    type Output = Vec3;
+
+    fn mul(self, rhs: Vec3) -> Self::Output {
+        self.apply(rhs)
+    }
+}
+
+impl Mul for Rot3 {
+    type Output = Rot3;
+
+    fn mul(self, rhs: Rot3) -> Self::Output {
+        self.compose(&rhs)
+    }
+}
+
+const fn gen_rotations() -> [Rot3; 24] {
+    // (permutation, sign of the permutation)
+    const PERMS: [([usize; 3], i64); 6] = [
+        ([0, 1, 2], 1),
+        ([0, 2, 1], -1),
+        ([1, 0, 2], -1),
+        ([1, 2, 0], 1),
+        ([2, 0, 1], 1),
+        ([2, 1, 0], -1),
+    ];
+    let mut ret = [Rot3::IDENTITY; 24];
+    let mut found = 0;
+    let mut p = 0;
+    while p < PERMS.len() {
+        let (perm, perm_sign) = PERMS[p];
+        let mut signs = 0;
+        while signs < 8 {
+            let s = [
+                if signs & 1 == 0 { 1 } else { -1 },
+                if signs & 2 == 0 { 1 } else { -1 },
+                if signs & 4 == 0 { 1 } else { -1 },
+            ];
+            // reflections have a determinant of -1.
+            if perm_sign * s[0] * s[1] * s[2] == 1 {
+                let mut m = [[0; 3]; 3];
+                m[0][perm[0]] = s[0];
+                m[1][perm[1]] = s[1];
+                m[2][perm[2]] = s[2];
+                ret[found] = Rot3(m);
+                found += 1;
+            }
+            signs += 1;
+        }
+        p += 1;
+    }
+    ret
+}
+
+/// Every orientation of a cube, the identity first.
+pub const ROTATIONS: [Rot3; 24] = gen_rotations();
+
+/// Rotation followed by a translation: `rot * p + offset`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Transform3 {
+    pub rot: Rot3,
+    pub offset: Vec3,
+}
+
+impl Transform3 {
+    pub const IDENTITY: Transform3 = Transform3 {
+        rot: Rot3::IDENTITY,
+        offset: Vec3::new(0, 0, 0),
+    };
+
+    pub const fn apply(&self, v: Vec3) -> Vec3 {
+        let r = self.rot.apply(v);
+        Vec3::new(
+            r.x + self.offset.x,
+            r.y + self.offset.y,
+            r.z + self.offset.z,
+        )
+    }
+
+    /// Transform applying `rhs` first, then self.
+    pub const fn compose(&self, rhs: &Transform3) -> Transform3 {
+        Transform3 {
+            rot: self.rot.compose(&rhs.rot),
+            offset: self.apply(rhs.offset),
+        }
+    }
+
+    pub const fn inverse(&self) -> Transform3 {
+        let rot = self.rot.inverse();
+        let o = rot.apply(self.offset);
+        Transform3 {
+            rot,
+            offset: Vec3::new(-o.x, -o.y, -o.z),
+        }
+    }
+}
+
+/// Find the rotation and translation that maps at least `min_overlap` of `points` onto `reference`,
+/// e.g. the position and orientation of a scanner relative to another one.
+///
+/// Tries every rotation, and counts how often each translation lines up a pair of points.
+pub fn align_points(reference: &[Vec3], points: &[Vec3], min_overlap: usize) -> Option<Transform3> {
+    let mut offsets = HashMap::new();
+    ROTATIONS.iter().find_map(|&rot| {
+        offsets.clear();
+        points.iter().find_map(|&p| {
+            let p = rot * p;
+            reference.iter().find_map(|&r| {
+                let count = offsets.entry(r - p).or_insert(0);
+                *count += 1;
+                (*count >= min_overlap).then_some(Transform3 { rot, offset: r - p })
+            })
+        })
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{align_points, Rot3, Transform3, Vec3, ROTATIONS};
+    use std::collections::HashSet;
+
+    #[test]
+    fn rotation_group() {
+        let unique = ROTATIONS.iter().collect::<HashSet<_>>();
+        assert_eq!(unique.len(), 24);
+        assert_eq!(ROTATIONS[0], Rot3::IDENTITY);
+        let v = Vec3::new(1, 2, 3);
+        let images = ROTATIONS.iter().map(|r| *r * v).collect::<HashSet<_>>();
+        assert_eq!(images.len(), 24);
+        for a in ROTATIONS {
+            assert_eq!(a * a.inverse(), Rot3::IDENTITY);
+            // closed under composition.
+            ROTATIONS
+                .iter()
+                .for_each(|&b| assert!(unique.contains(&(a * b))));
+        }
+    }
+
+    #[test]
+    fn align_point_cloud() {
+        let reference = [
+            (404, -588, -901),
+            (528, -643, 409),
+            (-838, 591, 734),
+            (390, -675, -793),
+            (-537, -823, -458),
+            (-485, -357, 347),
+            (-345, -311, 381),
+        ]
+        .map(Vec3::from);
+        let truth = Transform3 {
+            rot: ROTATIONS[17],
+            offset: Vec3::new(68, -1246, -43),
+        };
+        // seen from another scanner, plus a point only it can see.
+        let mut points = reference
+            .iter()
+            .map(|&p| truth.inverse().apply(p))
+            .collect::<Vec<_>>();
+        points.push(Vec3::new(1000, 1000, 1000));
+        assert_eq!(align_points(&reference, &points, 7), Some(truth));
+        assert_eq!(align_points(&reference, &points, 8), None);
+        assert_eq!(
+            truth.compose(&truth.inverse()).apply(Vec3::new(5, 6, 7)),
+            Vec3::new(5, 6, 7)
+        );
+        assert_eq!(Transform3::IDENTITY.apply(points[0]), points[0]);
+    }
+}
@@ -0,0 +1,318 @@
+//! Fixed-format line extraction, see [`scan!`](crate::scan!).
+//!
+//! A pattern is literal text with `{}` holes. Each hole captures the shortest run, within one line,
+//! up to the literal that follows it; a trailing hole captures the rest of the line.
+//! Two holes may not be adjacent, the split between them would be ambiguous.
+
+use crate::parse::ParseError;
+
+/// Number of `{}` holes in `pattern`; panics, at compile time when used in a const, on adjacent holes.
+pub const fn holes(pattern: &str) -> usize {
+    let pattern = pattern.as_bytes();
+    let mut count = 0;
+    let mut i = 0;
+    let mut last_hole_end = usize::MAX;
+    while i + 1 < pattern.len() {
+        if pattern[i] == b'{' && pattern[i + 1] == b'}' {
+            assert!(
+                last_hole_end != i,
+                "scan pattern has adjacent `{{}}` holes."
+            );
+            count += 1;
+            i += 2;
+            last_hole_end = i;
+        } else {
+            i += 1;
+        }
+    }
+    count
+}
+
+/// The literal text around the holes of `pattern`, `M` is one more than the number of holes.
+/// Meant to be evaluated once, in a const.
+pub const fn literals<const M: usize>(pattern: &str) -> [&[u8]; M] {
+    assert!(
+        holes(pattern) + 1 == M,
+        "scan pattern and fields differ in length."
+    );
+    let mut ret = [&[] as &[u8]; M];
+    let mut rest = pattern.as_bytes();
+    let mut k = 0;
+    let mut i = 0;
+    while i + 1 < rest.len() {
+        if rest[i] == b'{' && rest[i + 1] == b'}' {
+            let (literal, tail) = rest.split_at(i);
+            ret[k] = literal;
+            k += 1;
+            rest = tail.split_at(2).1;
+            i = 0;
+        } else {
+            i += 1;
+        }
+    }
+    ret[k] = rest;
+    ret
+}
+
+/// Extract `T` from a captured hole.
+pub trait FromScan<'a>: Sized {
+    /// What a capture should look like, for errors.
+    const EXPECTED: &'static str;
+
+    fn from_scan(capture: &'a [u8]) -> Option<Self>;
+}
+
+macro_rules! impl_from_scan_int {
+    ($($int:ty),*) => {
+        $(impl FromScan<'_> for $int {
+            const EXPECTED: &'static str = concat!("a number that fits in ", stringify!($int));
+
+            fn from_scan(capture: &[u8]) -> Option<Self> {
+                crate::parse_decimal(capture).ok()
+            }
+        })*
+    };
+}
+
+impl_from_scan_int!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+impl<'a> FromScan<'a> for &'a [u8] {
+    const EXPECTED: &'static str = "anything";
+
+    fn from_scan(capture: &'a [u8]) -> Option<Self> {
+        Some(capture)
+    }
+}
+
+impl<'a> FromScan<'a> for &'a str {
+    const EXPECTED: &'static str = "utf-8 text";
+
+    fn from_scan(capture: &'a [u8]) -> Option<Self> {
+        std::str::from_utf8(capture).ok()
+    }
+}
+
+impl FromScan<'_> for char {
+    const EXPECTED: &'static str = "a single character";
+
+    fn from_scan(capture: &[u8]) -> Option<Self> {
+        let mut chars = std::str::from_utf8(capture).ok()?.chars();
+        chars.next().filter(|_| chars.next().is_none())
+    }
+}
+
+fn error(input: &[u8], offset: usize, expected: impl Into<String>) -> ParseError {
+    let rest = &input[offset.min(input.len())..];
+    let found = match rest.iter().position(|&c| c == b'\n').unwrap_or(rest.len()) {
+        0 => "end of line".to_owned(),
+        end => format!("`{}`", String::from_utf8_lossy(&rest[..end])),
+    };
+    ParseError {
+        offset,
+        line: 1,
+        col: offset + 1,
+        expected: expected.into(),
+        found,
+    }
+}
+
+/// Match a pattern, split into its N + 1 [`literals`], at the start of `input`,
+/// returning the holes and the length matched. Stops at the end of the first line.
+pub fn match_prefix<'a, const N: usize>(
+    literals: &[&[u8]],
+    input: &'a [u8],
+) -> Result<([&'a [u8]; N], usize), ParseError> {
+    debug_assert_eq!(literals.len(), N + 1, "one more literal than holes");
+    let line = &input[..input
+        .iter()
+        .position(|&c| c == b'\n')
+        .unwrap_or(input.len())];
+    let mut literals = literals.iter().copied();
+    let mut captures = [&input[..0]; N];
+
+    let first = literals.next().unwrap_or_default();
+    if !line.starts_with(first) {
+        return Err(error(
+            input,
+            0,
+            format!("`{}`", String::from_utf8_lossy(first)),
+        ));
+    }
+    let mut pos = first.len();
+    for (capture, literal) in captures.iter_mut().zip(literals) {
+        let len = if literal.is_empty() {
+            line.len() - pos
+        } else {
+            line[pos..]
+                .windows(literal.len())
+                .position(|window| window == literal)
+                .ok_or_else(|| {
+                    error(
+                        input,
+                        pos,
+                        format!("`{}`", String::from_utf8_lossy(literal)),
+                    )
+                })?
+        };
+        if len == 0 {
+            return Err(error(input, pos, "a value"));
+        }
+        *capture = &line[pos..pos + len];
+        pos += len + literal.len();
+    }
+    Ok((captures, pos))
+}
+
+/// [`match_prefix`] against all of `line`.
+pub fn match_line<'a, const N: usize>(
+    literals: &[&[u8]],
+    line: &'a [u8],
+) -> Result<[&'a [u8]; N], ParseError> {
+    let (captures, len) = match_prefix(literals, line)?;
+    if len != line.len() {
+        return Err(error(line, len, "end of line"));
+    }
+    Ok(captures)
+}
+
+/// Convert the hole `capture`, a slice of `line`; `name` is only used in errors.
+pub fn field<'a, T: FromScan<'a>>(
+    line: &[u8],
+    capture: &'a [u8],
+    name: &str,
+) -> Result<T, ParseError> {
+    T::from_scan(capture).ok_or_else(|| {
+        let offset = capture.as_ptr() as usize - line.as_ptr() as usize;
+        let mut err = error(line, offset, format!("{} for `{name}`", T::EXPECTED));
+        err.found = format!("`{}`", String::from_utf8_lossy(capture));
+        err
+    })
+}
+
+/// Non-empty lines of `input`, with errors for a line moved to where it is in `input`.
+pub fn lines<'a, T, F>(
+    input: &'a [u8],
+    mut scan: F,
+) -> impl Iterator<Item = Result<T, ParseError>> + use<'a, T, F>
+where
+    F: FnMut(&'a [u8]) -> Result<T, ParseError>,
+{
+    let mut offset = 0;
+    input
+        .split(|&c| c == b'\n')
+        .enumerate()
+        .filter_map(move |(lineno, line)| {
+            let start = offset;
+            offset += line.len() + 1;
+            if line.is_empty() {
+                return None;
+            }
+            Some(scan(line).map_err(|err| ParseError {
+                offset: start + err.offset,
+                line: lineno + 1,
+                ..err
+            }))
+        })
+}
+
+/// Extract typed values from a fixed-format line, like a tiny `scanf`.
+/// The pattern is split at compile time, where the number of `{}` holes is also checked against the fields.
+///
+/// - `scan!(pattern, line => (name: Type, ...))`, the whole line must match: `Result<(Type, ...), ParseError>`.
+/// - `scan!(pattern, lines input => (...))`, every non-empty line: `impl Iterator<Item = Result<(Type, ...), ParseError>>`.
+/// - `scan!(pattern, prefix input => (...))`, only the start of `input`: `Result<((Type, ...), usize), ParseError>`,
+///   with the number of bytes matched.
+///
+/// Fields can be any integer, `char`, `&str` or `&[u8]`.
+///
+/// # Examples
+///
+/// ```
+/// use aoc_shared::scan;
+///
+/// let (a, b, c) = scan!("{} -> {},{}", b"7 -> -2,3" => (a: u32, b: i64, c: i64)).unwrap();
+/// assert_eq!((a, b, c), (7, -2, 3));
+///
+/// let err = scan!("{} -> {},{}", "7 -> x,3" => (a: u32, b: i64, c: i64)).unwrap_err();
+/// assert_eq!(err.to_string(), "1:6: expected a number that fits in i64 for `b`, found `x`");
+///
+/// let input = b"1-3 a: abcde\n\n2-9 c: ccccccccc\n";
+/// let policies = scan!("{}-{} {}: {}", lines input => (lo: usize, hi: usize, chr: char, pass: &str))
+///     .collect::<Result<Vec<_>, _>>()
+///     .unwrap();
+/// assert_eq!(policies[1], (2, 9, 'c', "ccccccccc"));
+///
+/// let ((x, y), len) = scan!("mul({},{})", prefix b"mul(2,4)do()" => (x: u32, y: u32)).unwrap();
+/// assert_eq!((x * y, len), (8, 8));
+/// ```
+///
+/// A pattern that doesn't fit the fields is a compile error:
+///
+/// ```compile_fail
+/// let _ = aoc_shared::scan!("{} -> {}", "1 -> 2" => (a: u32));
+/// ```
+///
+/// So are adjacent holes, which could never match:
+///
+/// ```compile_fail
+/// let _ = aoc_shared::scan!("{}{}", "12" => (a: u32, b: u32));
+/// ```
+#[macro_export]
+macro_rules! scan {
+    ($pattern:literal, lines $input:expr => ($($name:ident : $ty:ty),+ $(,)?)) => {
+        $crate::scan::lines(::std::convert::AsRef::<[u8]>::as_ref($input), |line| {
+            $crate::scan!($pattern, line => ($($name: $ty),+))
+        })
+    };
+    ($pattern:literal, prefix $input:expr => ($($name:ident : $ty:ty),+ $(,)?)) => {{
+        const N: usize = [$(stringify!($name)),+].len();
+        const LITERALS: [&[u8]; N + 1] = $crate::scan::literals($pattern);
+        let input: &[u8] = ::std::convert::AsRef::<[u8]>::as_ref($input);
+        $crate::scan::match_prefix::<N>(&LITERALS, input).and_then(|([$($name),+], len)| {
+            Ok((($($crate::scan::field::<$ty>(input, $name, stringify!($name))?,)+), len))
+        })
+    }};
+    ($pattern:literal, $input:expr => ($($name:ident : $ty:ty),+ $(,)?)) => {{
+        const N: usize = [$(stringify!($name)),+].len();
+        const LITERALS: [&[u8]; N + 1] = $crate::scan::literals($pattern);
+        let input: &[u8] = ::std::convert::AsRef::<[u8]>::as_ref($input);
+        $crate::scan::match_line::<N>(&LITERALS, input).and_then(|[$($name),+]| {
+            Ok(($($crate::scan::field::<$ty>(input, $name, stringify!($name))?,)+))
+        })
+    }};
+}
+
+#[cfg(test)]
+mod test {
+    use super::{holes, literals};
+
+    #[test]
+    #[should_panic]
+    fn adjacent_holes() {
+        holes("{}{}");
+    }
+
+    #[test]
+    fn patterns() {
+        assert_eq!(holes("{} -> {},{}"), 3);
+        assert_eq!(holes("no holes"), 0);
+        const MUL: [&[u8]; 3] = literals("mul({},{})");
+        assert_eq!(MUL, [&b"mul("[..], b",", b")"]);
+        assert_eq!(literals::<3>("{}x{}"), [&b""[..], b"x", b""]);
+
+        assert!(scan!("{}x{}x{}", b"2x3x4" => (l: u32, w: u32, h: u32)).is_ok());
+        let err = scan!("{}x{}x{}", b"2x3x" => (l: u32, w: u32, h: u32)).unwrap_err();
+        assert_eq!((err.col, err.expected.as_str()), (5, "a value"));
+        let err = scan!("{}x{}", b"2x3 " => (l: u32, w: u32)).unwrap_err();
+        assert_eq!((err.col, err.found.as_str()), (3, "`3 `"));
+        let err = scan!("turn {} {}", b"toggle 1" => (verb: &str, n: u32)).unwrap_err();
+        assert_eq!((err.col, err.expected.as_str()), (1, "`turn `"));
+
+        let input = "a to b = 1\nb to c = x\n";
+        let errs = scan!("{} to {} = {}", lines input => (from: &str, to: &str, cost: u32))
+            .filter_map(Result::err)
+            .collect::<Vec<_>>();
+        assert_eq!(errs.len(), 1);
+        assert_eq!((errs[0].line, errs[0].col, errs[0].offset), (2, 10, 20));
+    }
+}
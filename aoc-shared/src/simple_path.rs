@@ -0,0 +1,339 @@
+use std::collections::HashMap;
+
+use num::Zero;
+
+use crate::GridGraph;
+
+/// Bitset of visited node ids for a `CompactGraph`.
+pub type NodeSet = u128;
+
+/// Maximum amount of nodes a `CompactGraph` can hold, one per bit of a `NodeSet`.
+pub const MAX_COMPACT_NODES: usize = NodeSet::BITS as usize;
+
+/// Held-Karp tables grow by `2^n * n`, past this it's not worth trying.
+const MAX_HELD_KARP_NODES: usize = 24;
+
+/// Frontier size to grow to before handing the search off to rayon.
+#[cfg(feature = "rayon")]
+const PAR_FRONTIER: usize = 256;
+
+/// Small directed graph for exhaustive simple path searches (longest path, Hamiltonian routes, path counting).
+/// Visited nodes are tracked in a `NodeSet` so the graph may hold at most `MAX_COMPACT_NODES` nodes.
+#[derive(Debug, Clone)]
+pub struct CompactGraph<C> {
+    /// Adjacency list of (node id, cost), indexed by node id.
+    pub edges: Vec<Vec<(usize, C)>>,
+}
+
+impl<C> CompactGraph<C>
+where
+    C: Copy + Ord + Zero,
+{
+    pub fn new(len: usize) -> Self {
+        assert!(
+            len <= MAX_COMPACT_NODES,
+            "CompactGraph can only hold {MAX_COMPACT_NODES} nodes, got {len}."
+        );
+        CompactGraph {
+            edges: vec![vec![]; len],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.edges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.edges.is_empty()
+    }
+
+    pub fn add_edge(&mut self, from: usize, to: usize, cost: C) {
+        self.edges[from].push((to, cost));
+    }
+
+    /// Add an edge which can be walked in both directions.
+    pub fn add_bidi_edge(&mut self, a: usize, b: usize, cost: C) {
+        self.add_edge(a, b, cost);
+        self.add_edge(b, a, cost);
+    }
+
+    /// Longest simple path from `start` to `end`; None if `end` is unreachable.
+    pub fn longest_path(&self, start: usize, end: usize) -> Option<C> {
+        self.longest_from(start, end, 1 << start)
+    }
+
+    fn longest_from(&self, at: usize, end: usize, visited: NodeSet) -> Option<C> {
+        if at == end {
+            return Some(C::zero());
+        }
+        self.edges[at]
+            .iter()
+            .filter(|&&(next, _)| visited & (1 << next) == 0)
+            .filter_map(|&(next, cost)| {
+                self.longest_from(next, end, visited | (1 << next))
+                    .map(|path| path + cost)
+            })
+            .max()
+    }
+
+    /// Shortest path which visits every node exactly once, see: `longest_hamiltonian`.
+    pub fn shortest_hamiltonian(&self, start: Option<usize>) -> Option<C> {
+        self.held_karp(start, std::cmp::min)
+    }
+
+    /// Longest path which visits every node exactly once.
+    /// If `start` is None, the path may begin at any node. The path may end at any node.
+    /// This uses a `2^n * n` memo table, so it is only suitable for small graphs.
+    pub fn longest_hamiltonian(&self, start: Option<usize>) -> Option<C> {
+        self.held_karp(start, std::cmp::max)
+    }
+
+    fn held_karp(&self, start: Option<usize>, pick: fn(C, C) -> C) -> Option<C> {
+        let len = self.len();
+        assert!(
+            len <= MAX_HELD_KARP_NODES,
+            "Hamiltonian search is limited to {MAX_HELD_KARP_NODES} nodes, got {len}."
+        );
+        if len == 0 {
+            return None;
+        }
+        // memo[visited * len + node] = best cost of a path visiting `visited`, ending at `node`.
+        let mut memo: Vec<Option<C>> = vec![None; (1 << len) * len];
+        match start {
+            Some(start) => memo[(1 << start) * len + start] = Some(C::zero()),
+            None => (0..len).for_each(|node| memo[(1 << node) * len + node] = Some(C::zero())),
+        }
+        for visited in 1..(1usize << len) {
+            for node in 0..len {
+                let Some(cost) = memo[visited * len + node] else {
+                    continue;
+                };
+                for &(next, edge) in &self.edges[node] {
+                    if visited & (1 << next) != 0 {
+                        continue;
+                    }
+                    let slot = &mut memo[(visited | (1 << next)) * len + next];
+                    *slot = Some(slot.map_or(cost + edge, |old| pick(old, cost + edge)));
+                }
+            }
+        }
+        let full = (1 << len) - 1;
+        memo[full * len..].iter().flatten().copied().reduce(pick)
+    }
+
+    /// Count the paths from `start` to `end`, ignoring costs.
+    ///
+    /// Nodes in `repeatable` may be visited any number of times; they must not be adjacent to each other.
+    /// Otherwise up to `extra_visits` revisits of already visited nodes are allowed, except for `start`.
+    /// Paths stop as soon as they reach `end`.
+    ///
+    /// # Panics
+    ///
+    /// If two `repeatable` nodes are adjacent, as there would be infinitely many paths.
+    pub fn count_paths(
+        &self,
+        start: usize,
+        end: usize,
+        repeatable: NodeSet,
+        extra_visits: u32,
+    ) -> u64 {
+        let adjacent = self
+            .edges
+            .iter()
+            .enumerate()
+            .filter(|&(from, _)| repeatable & (1 << from) != 0)
+            .find_map(|(from, edges)| {
+                let &(to, _) = edges.iter().find(|&&(to, _)| repeatable & (1 << to) != 0)?;
+                Some((from, to))
+            });
+        assert!(
+            adjacent.is_none(),
+            "Repeatable nodes must not be adjacent, got an edge {adjacent:?}."
+        );
+        let mut memo = HashMap::new();
+        self.count_from(
+            start,
+            start,
+            end,
+            repeatable,
+            1 << start,
+            extra_visits,
+            &mut memo,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn count_from(
+        &self,
+        at: usize,
+        start: usize,
+        end: usize,
+        repeatable: NodeSet,
+        visited: NodeSet,
+        extra_visits: u32,
+        memo: &mut HashMap<(usize, NodeSet, u32), u64>,
+    ) -> u64 {
+        if at == end {
+            return 1;
+        } else if let Some(&paths) = memo.get(&(at, visited, extra_visits)) {
+            return paths;
+        }
+        let paths = self.edges[at]
+            .iter()
+            .filter(|&&(next, _)| next != start)
+            .map(|&(next, _)| {
+                let bit = 1 << next;
+                if repeatable & bit != 0 {
+                    self.count_from(next, start, end, repeatable, visited, extra_visits, memo)
+                } else if visited & bit == 0 {
+                    self.count_from(
+                        next,
+                        start,
+                        end,
+                        repeatable,
+                        visited | bit,
+                        extra_visits,
+                        memo,
+                    )
+                } else if extra_visits > 0 {
+                    self.count_from(
+                        next,
+                        start,
+                        end,
+                        repeatable,
+                        visited,
+                        extra_visits - 1,
+                        memo,
+                    )
+                } else {
+                    0
+                }
+            })
+            .sum();
+        memo.insert((at, visited, extra_visits), paths);
+        paths
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<C> CompactGraph<C>
+where
+    C: Copy + Ord + Zero + Send + Sync,
+{
+    /// Parallel version of `longest_path`.
+    /// The search is expanded breadth first until there is enough work to split across threads.
+    pub fn longest_path_par(&self, start: usize, end: usize) -> Option<C> {
+        use rayon::prelude::*;
+
+        let mut finished = None;
+        let mut frontier = vec![(start, 1 << start, C::zero())];
+        while !frontier.is_empty() && frontier.len() < PAR_FRONTIER {
+            frontier = frontier
+                .into_iter()
+                .flat_map(|(at, visited, cost): (usize, NodeSet, C)| {
+                    if at == end {
+                        finished = finished.max(Some(cost));
+                    }
+                    self.edges[at]
+                        .iter()
+                        .filter(move |&&(next, _)| at != end && visited & (1 << next) == 0)
+                        .map(move |&(next, edge)| (next, visited | (1 << next), cost + edge))
+                })
+                .collect();
+        }
+        frontier
+            .into_par_iter()
+            .filter_map(|(at, visited, cost)| {
+                self.longest_from(at, end, visited).map(|path| path + cost)
+            })
+            .max()
+            .max(finished)
+    }
+}
+
+impl From<&GridGraph> for CompactGraph<usize> {
+    fn from(graph: &GridGraph) -> Self {
+        let mut ret = CompactGraph::new(graph.len());
+        graph.edges.iter().enumerate().for_each(|(from, edges)| {
+            edges
+                .iter()
+                .for_each(|&(to, cost)| ret.add_edge(from, to, cost))
+        });
+        ret
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::CompactGraph;
+
+    fn cities() -> CompactGraph<u32> {
+        // London, Dublin, Belfast
+        let mut graph = CompactGraph::new(3);
+        graph.add_bidi_edge(0, 1, 464);
+        graph.add_bidi_edge(0, 2, 518);
+        graph.add_bidi_edge(1, 2, 141);
+        graph
+    }
+
+    #[test]
+    fn hamiltonian_routes() {
+        let graph = cities();
+        assert_eq!(graph.shortest_hamiltonian(None), Some(605));
+        assert_eq!(graph.longest_hamiltonian(None), Some(982));
+        assert_eq!(graph.shortest_hamiltonian(Some(2)), Some(605));
+        assert_eq!(graph.longest_hamiltonian(Some(1)), Some(982));
+    }
+
+    #[test]
+    fn longest_simple_path() {
+        let graph = cities();
+        assert_eq!(graph.longest_path(0, 2), Some(605));
+        assert_eq!(graph.longest_path(0, 1), Some(659));
+        let mut one_way = CompactGraph::new(3);
+        one_way.add_edge(0, 1, 1u32);
+        assert_eq!(one_way.longest_path(0, 2), None);
+        assert_eq!(one_way.longest_path(1, 0), None);
+    }
+
+    #[test]
+    fn count_cave_paths() {
+        // start-A, start-b, A-c, A-b, b-d, A-end, b-end
+        let (start, end, a, b, c, d) = (0, 1, 2, 3, 4, 5);
+        let mut graph = CompactGraph::new(6);
+        [
+            (start, a),
+            (start, b),
+            (a, c),
+            (a, b),
+            (b, d),
+            (a, end),
+            (b, end),
+        ]
+        .into_iter()
+        .for_each(|(l, r)| graph.add_bidi_edge(l, r, 1u32));
+        assert_eq!(graph.count_paths(start, end, 1 << a, 0), 10);
+        assert_eq!(graph.count_paths(start, end, 1 << a, 1), 36);
+    }
+
+    #[test]
+    #[should_panic(expected = "must not be adjacent")]
+    fn count_paths_adjacent_repeatable() {
+        let mut graph = CompactGraph::new(4);
+        graph.add_edge(0, 1, 1u32);
+        graph.add_bidi_edge(1, 2, 1);
+        graph.add_edge(2, 3, 1);
+        graph.count_paths(0, 3, 0b110, 0);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn longest_simple_path_par() {
+        let mut graph = CompactGraph::new(10);
+        (0..10)
+            .flat_map(|l| (0..10).map(move |r| (l, r)))
+            .filter(|(l, r)| l < r)
+            .for_each(|(l, r)| graph.add_bidi_edge(l, r, (l * 7 + r * 3) % 11));
+        assert_eq!(graph.longest_path_par(0, 9), graph.longest_path(0, 9));
+    }
+}
@@ -1,8 +1,14 @@
 use std::iter::FusedIterator;
 
+use crate::parse_decimal;
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum Token<'a> {
     Something(&'a [u8]),
+    /// Only emitted by [`AoCTokenizer::with_numbers`].
+    Number(i64),
+    /// Only emitted by [`AoCTokenizer::with_numbers`].
+    Word(&'a [u8]),
     Delimiter(u8),
     Newline,
     DoubleNewline,
@@ -10,9 +16,11 @@ pub enum Token<'a> {
     End,
 }
 
+#[derive(Clone)]
 pub struct AoCTokenizer<'a> {
     head: usize,
     done: bool,
+    numbers: bool,
     buffer: &'a [u8],
 }
 impl<'a> AoCTokenizer<'a> {
@@ -20,9 +28,33 @@ impl<'a> AoCTokenizer<'a> {
         AoCTokenizer {
             head: 0,
             done: false,
+            numbers: false,
             buffer: input,
         }
     }
+
+    /// Emit `Token::Number` for runs of digits and `Token::Word` for other runs, instead of `Token::Something`.
+    /// A `-` directly before the digits is folded in as a sign, unless it follows a word or number, as in `2-4`.
+    /// Numbers that don't fit in an i64 are emitted as words.
+    pub fn with_numbers(mut self) -> Self {
+        self.numbers = true;
+        self
+    }
+
+    /// Yield the (line, column) of each token too, both 1-based.
+    pub fn spanned(self) -> Spanned<'a> {
+        Spanned {
+            line: 1,
+            col: 1,
+            scanned: 0,
+            tokens: self,
+        }
+    }
+
+    /// Byte offset of the start of the next token.
+    pub fn offset(&self) -> usize {
+        self.head
+    }
 }
 
 impl<'a> From<&'a [u8]> for AoCTokenizer<'a> {
@@ -58,7 +90,28 @@ impl<'a> Iterator for AoCTokenizer<'a> {
                         break i;
                     }
                 };
-                Some(Token::Something(&self.buffer[start..self.head]))
+                let word = &self.buffer[start..self.head];
+                if !self.numbers {
+                    Some(Token::Something(word))
+                } else {
+                    Some(parse_decimal(word).map_or(Token::Word(word), Token::Number))
+                }
+            }
+            b'-' if self.numbers
+                && self.buffer.get(self.head).is_some_and(u8::is_ascii_digit)
+                && !(start > 0 && self.buffer[start - 1].is_ascii_alphanumeric()) =>
+            {
+                let mut i = self.head;
+                while i < self.buffer.len() && self.buffer[i].is_ascii_alphanumeric() {
+                    i += 1;
+                }
+                match parse_decimal(&self.buffer[start..i]).ok() {
+                    Some(num) => {
+                        self.head = i;
+                        Some(Token::Number(num))
+                    }
+                    None => Some(Token::Delimiter(b'-')),
+                }
             }
             b'\n' => {
                 if self.head < self.buffer.len() && self.buffer[self.head] == b'\n' {
@@ -76,6 +129,34 @@ impl<'a> Iterator for AoCTokenizer<'a> {
 
 impl FusedIterator for AoCTokenizer<'_> {}
 
+/// See [`AoCTokenizer::spanned`].
+pub struct Spanned<'a> {
+    line: usize,
+    col: usize,
+    scanned: usize,
+    tokens: AoCTokenizer<'a>,
+}
+
+impl<'a> Iterator for Spanned<'a> {
+    type Item = (usize, usize, Token<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.tokens.offset().min(self.tokens.buffer.len());
+        for &chr in &self.tokens.buffer[self.scanned..start] {
+            if chr == b'\n' {
+                self.line += 1;
+                self.col = 1;
+            } else {
+                self.col += 1;
+            }
+        }
+        self.scanned = start;
+        self.tokens.next().map(|token| (self.line, self.col, token))
+    }
+}
+
+impl FusedIterator for Spanned<'_> {}
+
 pub trait Tokenize<'a> {
     fn tokenize(self) -> AoCTokenizer<'a>;
 }
@@ -164,3 +245,66 @@ impl<'a, T: Iterator<Item = Token<'a>>> GroupTokens<'a, T> for T {
         RecordGrouper::new_from_tokens_with_rs(self, separator)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{AoCTokenizer, Token};
+
+    #[test]
+    fn signed_numbers() {
+        let tokens = AoCTokenizer::new(b"x=-12..3, 2-4 -9z")
+            .with_numbers()
+            .filter(|token| token != &Token::Space)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Word(b"x"),
+                Token::Delimiter(b'='),
+                Token::Number(-12),
+                Token::Delimiter(b'.'),
+                Token::Delimiter(b'.'),
+                Token::Number(3),
+                Token::Delimiter(b','),
+                Token::Number(2),
+                Token::Delimiter(b'-'),
+                Token::Number(4),
+                Token::Delimiter(b'-'),
+                Token::Word(b"9z"),
+                Token::End,
+            ]
+        );
+        assert_eq!(
+            AoCTokenizer::new(b"-9223372036854775808 9223372036854775808")
+                .with_numbers()
+                .collect::<Vec<_>>(),
+            vec![
+                Token::Number(i64::MIN),
+                Token::Space,
+                Token::Word(b"9223372036854775808"),
+                Token::End,
+            ]
+        );
+    }
+
+    #[test]
+    fn spanned() {
+        let spans = AoCTokenizer::new(b"ab 1\n\n-2\nc")
+            .with_numbers()
+            .spanned()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            spans,
+            vec![
+                (1, 1, Token::Word(b"ab")),
+                (1, 3, Token::Space),
+                (1, 4, Token::Number(1)),
+                (1, 5, Token::DoubleNewline),
+                (3, 1, Token::Number(-2)),
+                (3, 3, Token::Newline),
+                (4, 1, Token::Word(b"c")),
+                (4, 2, Token::End),
+            ]
+        );
+    }
+}
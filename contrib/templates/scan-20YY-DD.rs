@@ -1,20 +1,14 @@
 use std::io;
 
-use aoc_shared::read_input;
-use regex::bytes::Regex;
+use aoc_shared::{read_input, scan};
 
 type Output = Vec<!>;
 type Solved = i64;
 
 fn parse_input(input: &[u8]) -> Output {
-    let mut out = vec![];
-    let re = Regex::new(r##"-- (?<R>REGEX) (?<H>HERE) --"##).unwrap();
-    re.captures_iter(input).for_each(|matcher| {
-        let r = fold_decimal_from(matcher.name("R").unwrap().as_bytes());
-        let h = fold_decimal_from(matcher.name("H").unwrap().as_bytes());
-        out.push((r, h));
-    });
-    out
+    scan!("-- {} {} --", lines input => (r: i64, h: i64))
+        .map(|line| line.unwrap_or_else(|err| panic!("{err}")))
+        .collect()
 }
 
 fn part1_sol(input: &Output) -> Solved {}
@@ -1,6 +1,6 @@
 use std::io;
 
-use aoc_shared::{fold_decimal_from, read_input};
+use aoc_shared::{read_input, scan};
 
 #[derive(Debug)]
 struct Present {
@@ -45,16 +45,9 @@ impl Present {
 }
 
 fn parse_input(input: Vec<u8>) -> Vec<Present> {
-    let re = regex::bytes::Regex::new(
-        r#"(?m)^(?P<l>[[:digit:]]+)x(?P<w>[[:digit:]]+)x(?P<h>[[:digit:]]+)"#,
-    )
-    .unwrap();
-    re.captures_iter(&input)
-        .map(|c| {
-            let (_, [l, w, h]) = c.extract();
-            let l = fold_decimal_from(l);
-            let w = fold_decimal_from(w);
-            let h = fold_decimal_from(h);
+    scan!("{}x{}x{}", lines &input => (l: i64, w: i64, h: i64))
+        .map(|present| {
+            let (l, w, h) = present.unwrap_or_else(|err| panic!("{err}"));
             Present { l, w, h }
         })
         .collect::<Vec<Present>>()
@@ -1,7 +1,6 @@
 use std::{fmt::Display, io};
 
-use aoc_shared::{fold_decimal_from, read_input, FlatVec2D};
-use regex::bytes::Regex;
+use aoc_shared::{read_input, scan, FlatVec2D};
 
 #[derive(Copy, Clone)]
 enum Action {
@@ -85,52 +84,24 @@ impl<'a> IntoIterator for &'a Instruction {
 }
 
 fn parse(input: Vec<u8>) -> Vec<Instruction> {
-    let re = Regex::new(
-        r#"(?x)
-    (?:turn)?
-    [[:space:]]*
-    (?<verb>on|off|toggle)
-    [[:space:]]+
-    (?<start1>[[:digit:]]+),(?<start2>[[:digit:]]+)
-    [[:space:]]+
-    (?:through)
-    [[:space:]]+
-    (?<end1>[[:digit:]]+),(?<end2>[[:digit:]]+)
-    [[:space:]]*
-"#,
-    )
-    .unwrap();
-
-    re.captures_iter(&input)
-        .map(|captures| {
-            let verb = captures
-                .name("verb")
-                .expect("every input line needs an action.");
-
-            let start1 = captures
-                .name("start1")
-                .map(|m| fold_decimal_from(m.as_bytes()))
-                .expect("every input needs a starting x coord.");
-            let start2 = captures
-                .name("start2")
-                .map(|m| fold_decimal_from(m.as_bytes()))
-                .expect("every input needs a starting y coord.");
-
-            let end1 = captures
-                .name("end1")
-                .map(|m| fold_decimal_from(m.as_bytes()))
-                .expect("every input needs a ending x coord.");
-            let end2 = captures
-                .name("end2")
-                .map(|m| fold_decimal_from(m.as_bytes()))
-                .expect("every input needs a ending y coord.");
+    input
+        .split(|&c| c == b'\n')
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            // `turn on` and `turn off`, but just `toggle`.
+            let line = line.strip_prefix(b"turn ").unwrap_or(line);
+            let (verb, start1, start2, end1, end2) = scan!(
+                "{} {},{} through {},{}",
+                line => (verb: &[u8], start1: usize, start2: usize, end1: usize, end2: usize)
+            )
+            .unwrap_or_else(|err| panic!("{err}"));
 
             Instruction::new(
-                match verb.as_bytes() {
+                match verb {
                     b"on" => Action::On,
                     b"off" => Action::Off,
                     b"toggle" => Action::Toggle,
-                    _ => unreachable!(),
+                    _ => panic!("unknown action: {}", String::from_utf8_lossy(verb)),
                 },
                 (start1, start2),
                 (end1, end2),
@@ -92,6 +92,7 @@ fn parse_input(input: &'_ [u8]) -> Output<'_> {
     let (tokens, _, _) = AoCTokenizer::new(input)
         .map(|token| match token {
             Token::Something(word) => Chars(word),
+            Token::Number(_) | Token::Word(_) => unreachable!("numbers mode is off"),
             Token::Delimiter(b'\\') => UknkEsc,
             Token::Delimiter(b'"') => Quote,
             Token::Delimiter(delim) => Char(delim),
@@ -1,6 +1,4 @@
-use aoc_shared::{fold_decimal, read_input};
-use itertools::Itertools;
-use regex::bytes::Regex;
+use aoc_shared::{read_input, scan, CompactGraph};
 use std::{
     collections::{HashMap, HashSet},
     io,
@@ -19,33 +17,18 @@ fn gen_city_pair<'a>(city_a: &'a [u8], city_b: &'a [u8]) -> (&'a [u8], &'a [u8])
 }
 
 fn parse_input(input: &'_ [u8]) -> Output<'_> {
-    let re =
-        Regex::new(r##"(?m)^(?<city1>[A-Za-z]+) to (?<city2>[A-Za-z]+) = (?<cost>[[:digit:]]+)$"##)
-            .unwrap();
+    let (clist, ccost) =
+        scan!("{} to {} = {}", lines input => (start: &[u8], end: &[u8], cost: u64))
+            .map(|route| route.unwrap_or_else(|err| panic!("{err}")))
+            .fold(
+                (HashSet::new(), HashMap::new()),
+                |(mut cities, mut city_cost), (start, end, cost)| {
+                    city_cost.insert(gen_city_pair(start, end), cost);
+                    cities.extend([start, end]);
 
-    let (clist, ccost) = re.captures_iter(input).fold(
-        (HashSet::new(), HashMap::new()),
-        |(mut cities, mut city_cost), rematch| {
-            let start = rematch
-                .name("city1")
-                .expect("Should have matched: city1")
-                .as_bytes();
-            let end = rematch
-                .name("city2")
-                .expect("Should have matched: city2")
-                .as_bytes();
-            let cost = rematch
-                .name("cost")
-                .expect("Should have matched: cost")
-                .as_bytes()
-                .iter()
-                .fold(0u64, fold_decimal);
-            city_cost.insert(gen_city_pair(start, end), cost);
-            cities.extend([start, end]);
-
-            (cities, city_cost)
-        },
-    );
+                    (cities, city_cost)
+                },
+            );
     let clist = clist.iter().copied().collect::<Vec<&[u8]>>();
     (clist, ccost)
 }
@@ -54,24 +37,22 @@ fn part1_2_sol<'a>(
     cities: &[&'a [u8]],
     costs: &HashMap<(&'a [u8], &'a [u8]), u64>,
 ) -> (Solved, Solved) {
-    cities
-        .iter()
-        .copied()
-        .permutations(cities.len())
-        .flat_map(|perm| {
-            perm.iter().tuple_windows().try_fold(0u64, |acc, (c1, c2)| {
-                costs.get(&gen_city_pair(c1, c2)).map(|cost| acc + cost)
-            })
-        })
-        .fold((u64::MAX, u64::MIN), |(min, max), costs| {
-            if costs < min {
-                (costs, max)
-            } else if costs > max {
-                (min, costs)
-            } else {
-                (min, max)
+    let mut graph = CompactGraph::new(cities.len());
+    cities.iter().enumerate().for_each(|(i, c1)| {
+        cities.iter().enumerate().skip(i + 1).for_each(|(j, c2)| {
+            if let Some(&cost) = costs.get(&gen_city_pair(c1, c2)) {
+                graph.add_bidi_edge(i, j, cost);
             }
         })
+    });
+    (
+        graph
+            .shortest_hamiltonian(None)
+            .expect("a route through all cities"),
+        graph
+            .longest_hamiltonian(None)
+            .expect("a route through all cities"),
+    )
 }
 
 // fn part2_sol(input: Output) -> Solved {}
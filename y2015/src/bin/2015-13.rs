@@ -1,6 +1,5 @@
-use aoc_shared::{fold_decimal, read_input};
+use aoc_shared::{read_input, scan};
 use itertools::{chain, Itertools};
-use regex::bytes::Regex;
 use std::{
     collections::{HashMap, HashSet},
     io,
@@ -10,34 +9,19 @@ use std::{
 type Output<'a> = (Vec<&'a [u8]>, HashMap<(&'a [u8], &'a [u8]), i64>);
 
 fn parse_input(input: &'_ [u8]) -> Output<'_> {
-    let re =
-        Regex::new(r##"(?m)^(?<p1>[A-Za-z]+) would (?<verb>gain|lose) (?<value>[[:digit:]]+) happiness units by sitting next to (?<p2>[A-Za-z]+).$"##)
-            .unwrap();
-
-    let (plist, pcost) = re.captures_iter(input).fold(
+    let (plist, pcost) = scan!(
+        "{} would {} {} happiness units by sitting next to {}.",
+        lines input => (person1: &[u8], verb: &[u8], value: i64, person2: &[u8])
+    )
+    .map(|rule| rule.unwrap_or_else(|err| panic!("{err}")))
+    .fold(
         (HashSet::new(), HashMap::new()),
-        |(mut people, mut people_map), rematch| {
-            let person1 = rematch
-                .name("p1")
-                .expect("Should have matched: city1")
-                .as_bytes();
-            let person2 = rematch
-                .name("p2")
-                .expect("Should have matched: city2")
-                .as_bytes();
-
-            let sign = if rematch.name("verb").expect("should match").as_bytes() == b"gain" {
-                1
-            } else {
-                -1
+        |(mut people, mut people_map), (person1, verb, value, person2)| {
+            let value = match verb {
+                b"gain" => value,
+                b"lose" => -value,
+                _ => panic!("unknown verb: {}", String::from_utf8_lossy(verb)),
             };
-            let value = sign
-                * rematch
-                    .name("value")
-                    .expect("Should have matched: cost")
-                    .as_bytes()
-                    .iter()
-                    .fold(0i64, fold_decimal);
 
             people_map.insert((person1, person2), value);
             people.insert(person1);
@@ -1,5 +1,4 @@
-use aoc_shared::{fold_decimal, read_input};
-use regex::bytes::Regex;
+use aoc_shared::{read_input, scan};
 use std::io;
 
 type Output<'a> = Vec<(&'a [u8], Piecewise)>;
@@ -21,34 +20,15 @@ impl Piecewise {
 }
 
 fn parse_input(input: &'_ [u8]) -> Output<'_> {
-    let regex = Regex::new(r##"(?m)^(?<rein>[A-Za-z]+) can fly (?<linear>[[:digit:]]+) km/s for (?<upto>[[:digit:]]+) seconds, but then must rest for (?<rest>[[:digit:]]+) seconds.$"##)
-            .unwrap();
-
-    regex
-        .captures_iter(input)
-        .map(|cap| {
-            let reindeer = cap.name("rein").expect("to get reindeer name").as_bytes();
-            let linear = cap
-                .name("linear")
-                .expect("to get linear fn")
-                .as_bytes()
-                .iter()
-                .fold(0, fold_decimal);
-            let upto = cap
-                .name("upto")
-                .expect("to get upto sec")
-                .as_bytes()
-                .iter()
-                .fold(0, fold_decimal);
-            let rest = cap
-                .name("rest")
-                .expect("to get rest sec")
-                .as_bytes()
-                .iter()
-                .fold(0, fold_decimal);
-            (reindeer, Piecewise { linear, upto, rest })
-        })
-        .collect()
+    scan!(
+        "{} can fly {} km/s for {} seconds, but then must rest for {} seconds.",
+        lines input => (reindeer: &[u8], linear: i64, upto: i64, rest: i64)
+    )
+    .map(|reindeer| {
+        let (reindeer, linear, upto, rest) = reindeer.unwrap_or_else(|err| panic!("{err}"));
+        (reindeer, Piecewise { linear, upto, rest })
+    })
+    .collect()
 }
 
 fn solve(r: &[(&[u8], Piecewise)], time: i64) -> i64 {
@@ -1,5 +1,4 @@
 use aoc_shared::{read_input, Token, Tokenize};
-use regex::bytes::Regex;
 use std::{
     collections::{HashMap, HashSet},
     io,
@@ -47,6 +46,21 @@ fn parse_input(input: &'_ [u8]) -> (Vec<Replacer<'_>>, &'_ [u8]) {
 
 const LHS_TERM: u8 = 255;
 
+/// Elements of a molecule: an uppercase letter and any lowercase letters after it.
+fn elements(molecule: &[u8]) -> impl Iterator<Item = &[u8]> {
+    let mut rest = molecule;
+    std::iter::from_fn(move || {
+        let start = rest.iter().position(u8::is_ascii_uppercase)?;
+        let len = 1 + rest[start + 1..]
+            .iter()
+            .take_while(|c| c.is_ascii_lowercase())
+            .count();
+        let (element, tail) = rest[start..].split_at(len);
+        rest = tail;
+        Some(element)
+    })
+}
+
 fn parse_input2<'a>(replace: &[Replacer<'a>], molecule: &'a [u8]) -> (Vec<u8>, Vec<u8>) {
     let mut last = 0u8;
     let mut tok_list: HashMap<&[u8], u8> =
@@ -55,18 +69,12 @@ fn parse_input2<'a>(replace: &[Replacer<'a>], molecule: &'a [u8]) -> (Vec<u8>, V
             (*find, last)
         }));
 
-    // I don't feel like building my own.
-    let re = Regex::new(r#"([A-Z][a-z]*)"#).unwrap();
-
     // find all unique terminating tokens
     let terminals = replace
         .iter()
         .flat_map(|Replacer(_, replacement)| {
             // we have to collect matches first to properly filter tokens in tail position.
-            let matchers = re
-                .captures_iter(replacement)
-                .map(|m| m.get(0).unwrap().as_bytes())
-                .collect::<Vec<&'a [u8]>>();
+            let matchers = elements(replacement).collect::<Vec<&'a [u8]>>();
             matchers
                 .iter()
                 .enumerate()
@@ -89,12 +97,8 @@ fn parse_input2<'a>(replace: &[Replacer<'a>], molecule: &'a [u8]) -> (Vec<u8>, V
         .collect::<Vec<u8>>();
 
     // create new molecule using token numbers
-    let new_mol = re
-        .captures_iter(molecule)
-        .map(|m| {
-            let c = m.get(0).unwrap().as_bytes();
-            *tok_list.get(c).unwrap_or(&0)
-        })
+    let new_mol = elements(molecule)
+        .map(|c| *tok_list.get(c).unwrap_or(&0))
         .collect::<Vec<u8>>();
     (terminals, new_mol)
 }
@@ -1,7 +1,7 @@
-use std::io;
+use std::{io, thread};
 
 use itertools::Itertools;
-use y2019::intcode::{read_intcode, IntCode, IntCodeErr};
+use y2019::intcode::{network::channel, read_intcode, IntCode, IntCodeErr, Machine};
 
 fn run_program(program: &[i64]) -> i64 {
     (0..5).permutations(5).fold(i64::MIN, |acc, config_inputs| {
@@ -26,55 +26,45 @@ fn run_program(program: &[i64]) -> i64 {
     })
 }
 
-struct Feedback {
-    intcode: IntCode,
-    input: Option<i64>,
-    program: Vec<i64>,
-}
-
-fn run_feedback(
-    Feedback {
-        intcode,
-        input,
-        program,
-    }: &mut Feedback,
-    last_sig: i64,
-) -> Option<i64> {
-    loop {
-        match intcode.execute(program, input) {
-            Ok(None) => (),
-            Ok(out) => return out,
-            Err(IntCodeErr::NeedInput) => *input = Some(last_sig),
-            Err(IntCodeErr::End) => return None,
-            Err(e) => panic!("{e}"),
-        }
-    }
-}
-
+/// Runs the amplifiers on their own threads, connected in a loop by channels.
 fn feedback(program: &[i64]) -> i64 {
     (5..10)
         .permutations(5)
-        .fold(i64::MIN, |acc, config_inputs| {
-            // let config_inputs = [0i64, 1, 2, 3, 4];
-            let mut amps = config_inputs
+        .map(|phases| {
+            let (mut senders, inputs): (Vec<_>, Vec<_>) = phases
                 .into_iter()
-                .map(|phase| Feedback {
-                    intcode: IntCode::default(),
-                    input: Some(phase),
-                    program: program.to_vec(),
+                .map(|phase| {
+                    let (tx, rx) = channel();
+                    tx.send(phase).unwrap();
+                    (tx, rx)
                 })
-                .collect::<Vec<_>>();
-            let amps_len = amps.len();
-            let mut m_idx = 0;
-            let last = std::iter::successors(Some(0i64), move |&last| {
-                let m = &mut amps[m_idx];
-                m_idx = (m_idx + 1) % amps_len;
-                run_feedback(m, last)
-            })
-            .last()
-            .unwrap(); // never fails, is always Some(0) at a minimum.
-            last.max(acc)
+                .unzip();
+            senders[0].send(0).unwrap();
+            // each amplifier outputs to the next one.
+            senders.rotate_left(1);
+            let mut inputs = thread::scope(|scope| {
+                let amps = inputs
+                    .into_iter()
+                    .zip(senders)
+                    .map(|(input, mut tx)| {
+                        scope.spawn(move || {
+                            let mut input = input.blocking();
+                            Machine::new(program.to_vec())
+                                .run_until_halt(&mut input, &mut tx)
+                                .unwrap_or_else(|err| panic!("{err}"));
+                            input
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                amps.into_iter()
+                    .map(|amp| amp.join().unwrap())
+                    .collect::<Vec<_>>()
+            });
+            // the last signal is left over for the first amplifier, which already halted.
+            inputs[0].blocking_recv().unwrap()
         })
+        .max()
+        .unwrap()
 }
 
 fn main() -> io::Result<()> {
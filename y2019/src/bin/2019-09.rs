@@ -1,27 +1,22 @@
 use std::io;
 
-use y2019::intcode::{brk, read_intcode, IntCode, IntCodeErr};
+use y2019::intcode::{read_intcode, Machine, Status};
 
-fn run_program(mut program: Vec<i64>, i: i64) -> i64 {
-    let mut intcode = IntCode::default();
+fn run_program(program: Vec<i64>, i: i64) -> i64 {
+    let mut machine = Machine::new(program);
     let mut input = Some(i);
     let mut last = 0;
     loop {
-        match intcode.execute(&mut program, &mut input) {
-            Ok(None) => (),
-            Ok(Some(output)) => {
+        match machine.run_until_output(&mut input).expect("valid program") {
+            Status::Output(output) => {
                 #[cfg(debug_assertions)]
                 {
                     println!("Diag: {output}");
                 }
                 last = output;
             }
-            Err(IntCodeErr::NeedInput) => input = Some(i),
-            Err(IntCodeErr::End) => break,
-            Err(IntCodeErr::OutOfBounds(fault)) => {
-                brk(fault, &mut program).expect("To resize the program break.")
-            }
-            Err(e) => panic!("{e}"),
+            Status::NeedInput => input = Some(i),
+            Status::Halted => break,
         }
     }
     last
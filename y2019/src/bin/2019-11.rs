@@ -1,14 +1,14 @@
 use std::{collections::HashMap, io};
 
 use aoc_shared::rot::{rot_left, rot_right};
-use y2019::intcode::{brk, read_intcode, IntCode, IntCodeErr};
+use y2019::intcode::{read_intcode, Machine, Status};
 
 enum OutState {
     PaintColor,
     Rotate,
 }
 
-fn run_program(mut program: Vec<i64>, start: i64) -> i64 {
+fn run_program(program: Vec<i64>, start: i64) -> i64 {
     let (mut x, mut y) = (0isize, 0isize);
     let (mut dx, mut dy) = (0isize, -1isize); // pointing up
     let (mut minx, mut miny, mut maxx) = (0isize, 0isize, 0isize); // for output mapping
@@ -16,13 +16,12 @@ fn run_program(mut program: Vec<i64>, start: i64) -> i64 {
     let mut panels: HashMap<(isize, isize), bool> = HashMap::default();
     let mut uniq_painted = 0;
 
-    let mut intcode = IntCode::default();
+    let mut machine = Machine::new(program);
     let mut input = Some(start);
     let mut outstate = OutState::PaintColor;
     loop {
-        match intcode.execute(&mut program, &mut input) {
-            Ok(None) => (),
-            Ok(Some(output)) => {
+        match machine.run_until_output(&mut input).expect("valid program") {
+            Status::Output(output) => {
                 outstate = match outstate {
                     OutState::PaintColor => {
                         if let Some(panel) = panels.get_mut(&(x, y)) {
@@ -48,18 +47,14 @@ fn run_program(mut program: Vec<i64>, start: i64) -> i64 {
                     }
                 };
             }
-            Err(IntCodeErr::NeedInput) => {
+            Status::NeedInput => {
                 if let Some(panel) = panels.get(&(x, y)) {
                     input = Some(*panel as i64);
                 } else {
                     input = Some(false as i64);
                 }
             }
-            Err(IntCodeErr::End) => break,
-            Err(IntCodeErr::OutOfBounds(fault)) => {
-                brk(fault, &mut program).expect("Resize program")
-            }
-            Err(e) => panic!("{e}"),
+            Status::Halted => break,
         }
     }
     // part2
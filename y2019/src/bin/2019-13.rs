@@ -17,7 +17,7 @@ use termion::{
     raw::{IntoRawMode, RawTerminal},
 };
 
-use y2019::intcode::{brk, read_intcode, IntCode, IntCodeErr};
+use y2019::intcode::{read_intcode, Machine, Status};
 
 #[derive(Copy, Clone)]
 enum TileId {
@@ -142,8 +142,8 @@ fn write_tile<W: WriteT + AsFd>(tty: &mut RawTerminal<W>, x: u16, y: u16, maxy:
 
 const QUARTERS: i64 = 2;
 
-fn run_program(mut program: Vec<i64>) -> (i64, i64) {
-    let mut intcode = IntCode::default();
+fn run_program(program: Vec<i64>) -> (i64, i64) {
+    let mut machine = Machine::new(program);
     let mut input = None;
     let mut outstate = OutState::Init;
 
@@ -152,7 +152,7 @@ fn run_program(mut program: Vec<i64>) -> (i64, i64) {
     let mut ball_x = 0;
     let mut paddle_x = 0;
     let mut max_y = 0u16;
-    program[0] = QUARTERS;
+    machine.poke(0, QUARTERS).expect("address 0 to exist");
 
     #[cfg(feature = "term")]
     let mut tty = get_terminal();
@@ -161,8 +161,8 @@ fn run_program(mut program: Vec<i64>) -> (i64, i64) {
         if STOP.load(std::sync::atomic::Ordering::Relaxed) {
             break;
         }
-        match intcode.execute_til(&mut program, &mut input) {
-            Ok(output) => {
+        match machine.run_until_output(&mut input).expect("valid program") {
+            Status::Output(output) => {
                 outstate = outstate.next(output).expect("Valid Output.");
                 match outstate {
                     OutState::XYTile(x, y, tile) => {
@@ -188,16 +188,12 @@ fn run_program(mut program: Vec<i64>) -> (i64, i64) {
                     _ => (),
                 }
             }
-            Err(IntCodeErr::NeedInput) => match ball_x.cmp(&paddle_x) {
+            Status::NeedInput => match ball_x.cmp(&paddle_x) {
                 Ordering::Less => input = Some(-1),
                 Ordering::Equal => input = Some(0),
                 Ordering::Greater => input = Some(1),
             },
-            Err(IntCodeErr::OutOfBounds(fault)) => {
-                brk(fault, &mut program).expect("Resize program")
-            }
-            Err(IntCodeErr::End) => break,
-            Err(e) => panic!("{e}"),
+            Status::Halted => break,
         }
     }
 
@@ -1,7 +1,7 @@
 use std::{collections::HashSet, io};
 
 use aoc_shared::{Dijkstra, HeapState};
-use y2019::intcode::{read_intcode, IntCode};
+use y2019::intcode::{self, read_intcode, Machine};
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 enum Dir {
@@ -45,23 +45,24 @@ fn discover_map(program: Vec<i64>) -> (HashSet<(i32, i32)>, (i32, i32)) {
     // Yes, I am effectively cloning the machine (incl memory) for each branching action.
     // This reduces the complexity of managing backtracking in a singular intcode machine.
     // This comes with the cost of extra memory.
-    let mut stack = vec![(0, 0, IntCode::default(), program)];
-    while let Some((x, y, intcode, prog)) = stack.pop() {
+    let mut stack = vec![(0, 0, Machine::new(program))];
+    while let Some((x, y, machine)) = stack.pop() {
         for (d, dx, dy) in DIRS {
             let (newx, newy) = (x + dx, y + dy);
-            let mut ic = intcode;
-            let mut nprog = prog.clone();
-            let out: Status = ic
-                .execute_til(&mut nprog, &mut Some(d as i64))
+            let mut branch = machine.clone();
+            let intcode::Status::Output(out) = branch
+                .run_until_output(&mut Some(d as i64))
                 .expect("No error")
-                .try_into()
-                .expect("valid status");
+            else {
+                panic!("droid to report a status");
+            };
+            let out: Status = out.try_into().expect("valid status");
             match out {
                 Status::Wall => (),
                 Status::AtLoc => target = (newx, newy),
                 Status::Moved => {
                     if visit.insert((newx, newy)) {
-                        stack.push((newx, newy, ic, nprog));
+                        stack.push((newx, newy, branch));
                     }
                 }
             }
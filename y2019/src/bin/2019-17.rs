@@ -1,16 +1,16 @@
 use std::{
-    collections::{HashMap, VecDeque},
+    collections::HashMap,
     fmt::{Display, Write as WriteF},
-    io::{self, Write},
+    io,
 };
 
 use aoc_shared::{
-    debug, destructure_or_none, pad_to_flat2d,
+    debug, destructure_or_none,
     rot::{rot_left, rot_right, EAST as E, NORTH as N, SOUTH as S, WEST as W},
     FlatVec2D, Neighbor,
 };
 use itertools::Itertools;
-use y2019::intcode::{brk, read_intcode, IntCode, IntCodeErr};
+use y2019::intcode::{ascii::AsciiMachine, read_intcode, Machine};
 
 #[derive(Default, Copy, Clone)]
 enum Scaff {
@@ -61,20 +61,15 @@ struct Pos {
     dy: isize,
 }
 
-fn get_scaffolding(mut prog: Vec<i64>) -> (FlatVec2D<Scaff>, Pos) {
-    let mut map = vec![];
-    let mut intcode = IntCode::default();
-    loop {
-        match intcode.execute_til(&mut prog, &mut None) {
-            Ok(o) => map.push(u8::try_from(o).expect("Valid ASCII")),
-            Err(IntCodeErr::OutOfBounds(newbrk)) => {
-                brk(newbrk, &mut prog).expect("to increase program brk")
-            }
-            Err(IntCodeErr::End) => break,
-            Err(e) => panic!("Unexpected ASCII failure: {e}"),
-        }
+fn get_scaffolding(prog: Vec<i64>) -> (FlatVec2D<Scaff>, Pos) {
+    let view = AsciiMachine::new(prog)
+        .read_view()
+        .unwrap_or_else(|e| panic!("Unexpected ASCII failure: {e}"));
+    let (xdim, ydim) = (view.1 + 2, view.2 + 2);
+    let mut ret = FlatVec2D(vec![Scaff::Border; xdim * ydim], xdim, ydim);
+    for (x, y) in view.xyrange() {
+        ret[(x + 1, y + 1)] = view[(x, y)].into();
     }
-    let ret = pad_to_flat2d(&map, Scaff::Border);
     debug!("{ret:?}");
     let mut start = None;
     for (x, y) in ret.pad_xyrange() {
@@ -236,7 +231,7 @@ fn get_patt_perm<'a>(
         .map(|patt| (patt, flatten_placeholders(prog, patt, with)))
 }
 
-fn get_program(p: &[Moves]) -> Option<VecDeque<u8>> {
+fn get_program(p: &[Moves]) -> Option<String> {
     let base = [Prog::Place(p.to_vec())];
     for (a_pattern, a_prog) in get_patt_perm(&base, &Prog::A) {
         for (b_pattern, b_prog) in get_patt_perm(&a_prog, &Prog::B) {
@@ -253,9 +248,7 @@ fn get_program(p: &[Moves]) -> Option<VecDeque<u8>> {
                     let b = b_pattern.iter().join(",");
                     let c = c_pattern.iter().join(",");
                     debug!("{main_r}\n{a}\n{b}\n{c}\nn\n");
-                    let mut vd = VecDeque::new();
-                    write!(vd, "{main_r}\n{a}\n{b}\n{c}\nn\n").unwrap();
-                    return Some(vd);
+                    return Some(format!("{main_r}\n{a}\n{b}\n{c}\nn"));
                 }
             }
         }
@@ -275,7 +268,7 @@ fn get_path_and_program(
         mut dx,
         mut dy,
     }: Pos,
-) -> VecDeque<u8> {
+) -> String {
     let mut path = vec![];
     loop {
         // blow right through intersections, do not branch and traverse all permutations.
@@ -321,28 +314,23 @@ fn get_path_and_program(
     get_program(&path).expect("Valid Intcode ascii program")
 }
 
-fn part2(mut prog: Vec<i64>, mut ascii: VecDeque<u8>) -> i64 {
-    let mut last = 0;
-    let mut intcode = IntCode::default();
-    prog[0] = 2;
-    loop {
-        match intcode.execute_til(&mut prog, &mut ascii) {
-            Ok(o) => last = o,
-            Err(IntCodeErr::OutOfBounds(newbrk)) => {
-                brk(newbrk, &mut prog).expect("to increase program brk")
-            }
-            Err(IntCodeErr::End) => break last,
-            Err(e) => panic!("Unexpected ASCII failure: {e}"),
-        }
-    }
+fn part2(prog: Vec<i64>, routines: &str) -> i64 {
+    let mut machine = Machine::new(prog);
+    machine.poke(0, 2).expect("address 0 to exist");
+    let mut robot = AsciiMachine::from(machine);
+    routines.lines().for_each(|line| robot.send_line(line));
+    robot
+        .read_until_prompt()
+        .unwrap_or_else(|e| panic!("Unexpected ASCII failure: {e}"));
+    robot.values().last().copied().unwrap_or_default()
 }
 
 fn main() -> io::Result<()> {
     let program = read_intcode()?;
     let (map, pos) = get_scaffolding(program.clone());
     let (p1, intersections) = part1(&map);
-    let ascii = get_path_and_program(&map, intersections, pos);
-    let p2 = part2(program, ascii);
+    let routines = get_path_and_program(&map, intersections, pos);
+    let p2 = part2(program, &routines);
     println!("Part1: {p1}, Part2: {p2}");
     Ok(())
 }
@@ -1,6 +1,6 @@
 use std::io;
 
-use y2019::intcode::{brk, read_intcode, Input, IntCode};
+use y2019::intcode::{read_intcode, Input, Machine, Status};
 
 enum Coord {
     XY(i64, i64),
@@ -31,11 +31,10 @@ fn find_tractor_beam(program: &[i64]) -> i64 {
     for y in 0..50 {
         for x in 0..50 {
             let mut input = Coord::XY(x, y);
-            let mut intcode = IntCode::default();
-            let mut prog = program.to_vec();
+            let mut machine = Machine::new(program.to_vec());
             loop {
-                match intcode.execute_til(&mut prog, &mut input) {
-                    Ok(out) => {
+                match machine.run_until_output(&mut input) {
+                    Ok(Status::Output(out)) => {
                         #[cfg(debug_assertions)]
                         if out == 1 {
                             print!("#");
@@ -44,11 +43,8 @@ fn find_tractor_beam(program: &[i64]) -> i64 {
                         }
                         output += out;
                     }
-                    Err(y2019::intcode::IntCodeErr::OutOfBounds(oob)) => {
-                        brk(oob, &mut prog).expect("Out of Memory.")
-                    }
-                    Err(y2019::intcode::IntCodeErr::End) => break,
-                    Err(e) => panic!("Unexpected error: {e}"),
+                    Ok(Status::Halted) => break,
+                    res => panic!("Unexpected result: {res:?}"),
                 }
             }
         }
@@ -68,17 +64,9 @@ fn find_santa_space_sled_dist(program: Vec<i64>) -> i64 {
         let first_x = (last_x..2i64.pow(20))
             .find(|&x| {
                 let mut input = Coord::XY(x, y);
-                let mut intcode = IntCode::default();
-                let mut prog = program.to_vec();
-                loop {
-                    match intcode.execute_til(&mut prog, &mut input) {
-                        Ok(1) => break true,
-                        Ok(_) => break false,
-                        Err(y2019::intcode::IntCodeErr::OutOfBounds(oob)) => {
-                            brk(oob, &mut prog).expect("Out of Memory.")
-                        }
-                        Err(e) => panic!("Unexpected error: {e}"),
-                    }
+                match Machine::new(program.to_vec()).run_until_output(&mut input) {
+                    Ok(Status::Output(out)) => out == 1,
+                    res => panic!("Unexpected result: {res:?}"),
                 }
             })
             .expect("Empty line post Y=100?");
@@ -86,17 +74,10 @@ fn find_santa_space_sled_dist(program: Vec<i64>) -> i64 {
         debug_assert!(last_x <= first_x);
         last_x = first_x;
         let mut input = Coord::XY(first_x + 99, y - 99);
-        let mut intcode = IntCode::default();
-        let mut prog = program.to_vec();
-        loop {
-            match intcode.execute_til(&mut prog, &mut input) {
-                Ok(1) => return first_x * 10000 + (y - 99),
-                Ok(_) => break,
-                Err(y2019::intcode::IntCodeErr::OutOfBounds(oob)) => {
-                    brk(oob, &mut prog).expect("Out of Memory.")
-                }
-                Err(e) => panic!("Unexpected error: {e}"),
-            }
+        match Machine::new(program.to_vec()).run_until_output(&mut input) {
+            Ok(Status::Output(1)) => return first_x * 10000 + (y - 99),
+            Ok(Status::Output(_)) => (),
+            res => panic!("Unexpected result: {res:?}"),
         }
     }
     0
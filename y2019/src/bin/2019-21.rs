@@ -1,9 +1,7 @@
-use std::{
-    collections::VecDeque,
-    io::{self, Write as _},
-};
+use std::io;
 
-use y2019::intcode::{brk, read_intcode, IntCode};
+use aoc_shared::debug;
+use y2019::intcode::{ascii::AsciiMachine, read_intcode};
 
 // (     we must jump if 3 tiles ahead is a hole: NOT C T because there are "islands like #.#.#
 //   AND we must land 4 tiles ahead if we are to jump: AND D T, while concerned about island
@@ -39,29 +37,19 @@ OR T J
 RUN
 ";
 
-fn solve(mut program: Vec<i64>, spring_script: &str) -> i64 {
-    let mut output = 0;
-    let mut springscript = VecDeque::new();
-    write!(springscript, "{spring_script}").unwrap();
-    let mut intcode = IntCode::default();
-    loop {
-        match intcode.execute_til(&mut program, &mut springscript) {
-            Ok(out) => {
-                if out / 2i64.pow(7) > 0 {
-                    output = out;
-                } else {
-                    #[cfg(debug_assertions)]
-                    print!("{}", char::from(out as u8));
-                }
-            }
-            Err(y2019::intcode::IntCodeErr::OutOfBounds(oob)) => {
-                brk(oob, &mut program).expect("Out of Memory.")
-            }
-            Err(y2019::intcode::IntCodeErr::End) => break,
-            Err(e) => panic!("Unexpected error: {e}"),
-        }
-    }
-    output
+fn solve(program: Vec<i64>, spring_script: &str) -> i64 {
+    let mut droid = AsciiMachine::new(program);
+    spring_script.lines().for_each(|line| droid.send_line(line));
+    let text = droid
+        .read_until_prompt()
+        .unwrap_or_else(|e| panic!("Unexpected error: {e}"));
+    debug!("{text}");
+    // the hull damage, unless the droid fell in and it printed its last moments instead.
+    droid
+        .values()
+        .last()
+        .copied()
+        .unwrap_or_else(|| panic!("Droid fell:\n{text}"))
 }
 
 fn main() -> io::Result<()> {
@@ -0,0 +1,10 @@
+use std::io;
+
+use y2019::intcode::{ascii::AsciiMachine, read_intcode};
+
+/// Play an ASCII intcode program on stdin and stdout, e.g. the text adventure of day 25:
+/// `intcode-ascii input/25`.
+fn main() -> io::Result<()> {
+    let mut machine = AsciiMachine::new(read_intcode()?);
+    machine.interact(io::stdin().lock(), io::stdout().lock())
+}
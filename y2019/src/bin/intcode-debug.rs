@@ -0,0 +1,165 @@
+use std::{
+    env, fs,
+    io::{self, BufRead, Write},
+    process,
+};
+
+use aoc_shared::ints;
+use y2019::intcode::{
+    debug::{Debugger, Stop},
+    disasm::Instr,
+};
+
+const HELP: &str = "\
+s|step [n]          execute n instructions
+b|back [n]          undo n instructions
+c|continue [n]      run until something stops it, or for at most n instructions
+break <addr>        stop before executing addr
+delete <addr>       remove a breakpoint
+watch <addr>        stop after a write to addr
+unwatch <addr>      remove a watchpoint
+r|regs              show pc and rb
+x <addr> [len]      dump memory
+l|list [addr] [n]   disassemble n instructions from addr, or pc
+i|input <n>...      queue numbers as input
+a|ascii <text>      queue a line of ASCII input
+out                 show all output, as text if it is ASCII
+trace on|off        log every executed instruction
+q|quit";
+
+fn report(dbg: &mut Debugger, stop: Option<Stop>) {
+    for line in dbg.take_trace() {
+        println!("{line}");
+    }
+    match stop {
+        None | Some(Stop::Limit) => (),
+        Some(Stop::Breakpoint(addr)) => println!("breakpoint at {addr}"),
+        Some(Stop::Watchpoint { addr, old, new }) => println!("watchpoint {addr}: {old} -> {new}"),
+        Some(Stop::NeedInput) => println!("waiting on input"),
+        Some(Stop::Halted) => println!("halted"),
+        Some(Stop::Fault(err)) => println!("fault: {err}"),
+    }
+    println!("{}", dbg.describe(dbg.state().pc));
+}
+
+fn show_output(outputs: &[i64]) {
+    match outputs
+        .iter()
+        .map(|&out| u8::try_from(out).ok().filter(u8::is_ascii).map(char::from))
+        .collect::<Option<String>>()
+    {
+        Some(text) => print!("{text}"),
+        None => println!("{outputs:?}"),
+    }
+}
+
+/// `args[i]` as a number, or `default` if missing.
+fn arg<T: std::str::FromStr>(args: &[&str], i: usize, default: T) -> Result<T, String> {
+    match args.get(i) {
+        Some(_) => required(args, i),
+        None => Ok(default),
+    }
+}
+
+/// `args[i]` as a number, which has to be there.
+fn required<T: std::str::FromStr>(args: &[&str], i: usize) -> Result<T, String> {
+    match args.get(i) {
+        Some(arg) => arg.parse().map_err(|_| format!("not a number: {arg}")),
+        None => Err(format!("{} needs an argument", args[0])),
+    }
+}
+
+fn command(dbg: &mut Debugger, line: &str) -> Result<bool, String> {
+    let args = line.split_whitespace().collect::<Vec<_>>();
+    let Some(&cmd) = args.first() else {
+        return Ok(true);
+    };
+    let seen = dbg.outputs().len();
+    match cmd {
+        "s" | "step" => {
+            let mut stop = None;
+            for _ in 0..arg(&args, 1, 1usize)? {
+                stop = dbg.step();
+                if stop.is_some() {
+                    break;
+                }
+            }
+            report(dbg, stop);
+        }
+        "b" | "back" => {
+            let n = arg(&args, 1, 1usize)?;
+            let undone = (0..n).take_while(|_| dbg.step_back()).count();
+            if undone < n {
+                println!("no more history");
+            }
+            report(dbg, None);
+        }
+        "c" | "continue" => {
+            let stop = dbg.run_for(arg(&args, 1, usize::MAX)?);
+            report(dbg, Some(stop));
+        }
+        "break" => dbg.break_at(arg(&args, 1, dbg.state().pc)?),
+        "delete" => {
+            if !dbg.clear_break(arg(&args, 1, dbg.state().pc)?) {
+                println!("no such breakpoint");
+            }
+        }
+        "watch" => dbg.watch(required(&args, 1)?),
+        "unwatch" => {
+            if !dbg.unwatch(required(&args, 1)?) {
+                println!("no such watchpoint");
+            }
+        }
+        "r" | "regs" => println!("pc={} rb={}", dbg.state().pc, dbg.state().rb),
+        "x" => print!("{}", dbg.dump(arg(&args, 1, 0)?, arg(&args, 2, 16)?)),
+        "l" | "list" => {
+            let mut addr = arg(&args, 1, dbg.state().pc)?;
+            for _ in 0..arg(&args, 2, 10usize)? {
+                let line = dbg.describe(addr);
+                println!("{line}");
+                // unknown cells are listed one by one.
+                addr += Instr::decode(dbg.memory(), addr).map_or(1, |instr| instr.width());
+            }
+        }
+        "i" | "input" => {
+            for i in 1..args.len() {
+                dbg.push_input(arg(&args, i, 0)?);
+            }
+        }
+        "a" | "ascii" => dbg.push_line(line.trim_start()[cmd.len()..].trim_start()),
+        "out" => show_output(dbg.outputs()),
+        "trace" => dbg.set_trace(args.get(1) != Some(&"off")),
+        "q" | "quit" => return Ok(false),
+        _ => println!("{HELP}"),
+    }
+    if dbg.outputs().len() > seen && cmd != "out" {
+        show_output(&dbg.outputs()[seen..]);
+    }
+    Ok(true)
+}
+
+/// Interactive debugger for intcode programs, e.g. `intcode-debug input/21`; type `help` for commands.
+fn main() -> io::Result<()> {
+    let Some(path) = env::args().nth(1) else {
+        eprintln!("usage: intcode-debug <program>");
+        process::exit(1);
+    };
+    let mut dbg = Debugger::new(ints(&fs::read(path)?).collect());
+    println!("{}", dbg.describe(0));
+
+    let stdin = io::stdin().lock();
+    let mut lines = stdin.lines();
+    loop {
+        print!("(icdb) ");
+        io::stdout().flush()?;
+        let Some(line) = lines.next() else {
+            break;
+        };
+        match command(&mut dbg, &line?) {
+            Ok(true) => (),
+            Ok(false) => break,
+            Err(err) => println!("{err}"),
+        }
+    }
+    Ok(())
+}
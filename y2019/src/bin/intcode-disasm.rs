@@ -0,0 +1,10 @@
+use std::io;
+
+use y2019::intcode::{disasm::disasm, read_intcode};
+
+/// Print the annotated assembly of an intcode program, e.g. `intcode-disasm input/21`.
+fn main() -> io::Result<()> {
+    let program = read_intcode()?;
+    print!("{}", disasm(&program));
+    Ok(())
+}
@@ -1,17 +1,21 @@
-use aoc_shared::read_input_to_string;
-use std::{collections::VecDeque, io};
+use aoc_shared::{ints, read_input};
+use std::{collections::VecDeque, io, sync::mpsc};
+
+pub mod ascii;
+pub mod asm;
+pub mod debug;
+pub mod disasm;
+mod machine;
+pub use machine::*;
+pub mod network;
 
 pub fn read_intcode() -> io::Result<Vec<i64>> {
-    let values = read_input_to_string()?;
-    Ok(values
-        .split(|c: char| !c.is_ascii_digit() && c != '-')
-        .filter(|s| !s.is_empty())
-        .map(|num| num.parse::<i64>().expect("Invalid number."))
-        .collect::<Vec<i64>>())
+    Ok(ints(&read_input()?).collect())
 }
 
-#[derive(Copy, Clone, Debug)]
-enum PMode {
+/// Parameter mode, the hundreds, thousands and ten thousands digits of an instruction.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PMode {
     Pos = 0,
     Imm = 1,
     Rel = 2,
@@ -30,8 +34,9 @@ impl TryFrom<i64> for PMode {
     }
 }
 
-#[derive(Debug)]
-enum Op {
+/// Operation, the last two digits of an instruction.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Op {
     Add = 1,
     Mul = 2,
     Inp = 3,
@@ -44,6 +49,62 @@ enum Op {
     End = 99,
 }
 
+impl Op {
+    pub const ALL: [Op; 10] = [
+        Op::Add,
+        Op::Mul,
+        Op::Inp,
+        Op::Out,
+        Op::Jit,
+        Op::Jif,
+        Op::Lt,
+        Op::Eq,
+        Op::Rba,
+        Op::End,
+    ];
+
+    /// Assembly name, as used by [`disasm`].
+    pub fn mnemonic(self) -> &'static str {
+        match self {
+            Op::Add => "ADD",
+            Op::Mul => "MUL",
+            Op::Inp => "IN",
+            Op::Out => "OUT",
+            Op::Jit => "JNZ",
+            Op::Jif => "JZ",
+            Op::Lt => "LT",
+            Op::Eq => "EQ",
+            Op::Rba => "ARB",
+            Op::End => "HLT",
+        }
+    }
+
+    /// Number of parameters.
+    pub fn arity(self) -> usize {
+        match self {
+            Op::Add | Op::Mul | Op::Lt | Op::Eq => 3,
+            Op::Jit | Op::Jif => 2,
+            Op::Inp | Op::Out | Op::Rba => 1,
+            Op::End => 0,
+        }
+    }
+
+    /// True if the last parameter is written to.
+    pub fn writes(self) -> bool {
+        matches!(self, Op::Add | Op::Mul | Op::Lt | Op::Eq | Op::Inp)
+    }
+}
+
+/// Instruction for `opcode` with `modes` for its parameters, unused modes are 0.
+fn encode(opcode: Op, modes: &[PMode]) -> i64 {
+    modes
+        .iter()
+        .rev()
+        .fold(0, |acc, &mode| acc * 10 + mode as i64)
+        * 100
+        + opcode as i64
+}
+
 #[derive(Debug)]
 struct Oper {
     p1: PMode,
@@ -52,7 +113,7 @@ struct Oper {
     opcode: Op,
 }
 
-#[derive(thiserror::Error, Debug)]
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
 pub enum IntCodeErr {
     #[error("Received an invalid operation: {0}")]
     InvalidOp(i64),
@@ -222,6 +283,30 @@ where
     }
 }
 
+/// Where outputs go, see [`Machine::run_until_input`].
+pub trait Output {
+    fn output(&mut self, value: i64);
+}
+
+impl Output for Vec<i64> {
+    fn output(&mut self, value: i64) {
+        self.push(value)
+    }
+}
+
+impl Output for VecDeque<i64> {
+    fn output(&mut self, value: i64) {
+        self.push_back(value)
+    }
+}
+
+/// Outputs after the receiver hung up are dropped, like a machine writing to a closed pipe.
+impl Output for mpsc::Sender<i64> {
+    fn output(&mut self, value: i64) {
+        _ = self.send(value)
+    }
+}
+
 impl IntCode {
     /// Calls `execute` repeatedly til the machine yields, due to IO or Error.
     pub fn execute_til<I: Input>(
@@ -310,11 +395,11 @@ mod tests {
     use std::collections::VecDeque;
     use std::io::Write as _;
 
-    use crate::intcode::{brk, IntCode, IntCodeErr};
+    use crate::intcode::{asm::assemble, brk, IntCode, IntCodeErr};
 
     #[test]
     fn immediate_mode() {
-        let mut program = vec![1001i64, 4, 11111, 0, 99];
+        let mut program = assemble("ADD [4], #11111 -> [0]\nHLT").unwrap();
         let mut intcode = IntCode::default();
         let Ok(None) = intcode.execute(&mut program, &mut None) else {
             panic!("unexpected error");
@@ -325,7 +410,7 @@ mod tests {
 
     #[test]
     fn input() {
-        let mut program = vec![3, 2, 99];
+        let mut program = assemble("IN -> [2]\nHLT").unwrap();
         let mut input = Some(111);
         let mut intcode = IntCode::default();
         assert!(intcode.execute(&mut program, &mut input).is_ok());
@@ -335,7 +420,7 @@ mod tests {
 
     #[test]
     fn output() {
-        let mut program = vec![4, 2, 99];
+        let mut program = assemble("OUT [2]\nHLT").unwrap();
         let mut intcode = IntCode::default();
         match intcode.execute(&mut program, &mut None) {
             Ok(Some(99)) => (),
@@ -345,7 +430,7 @@ mod tests {
 
     #[test]
     fn relative_base_and_mode() {
-        let mut program = vec![109, 19, 204, -34];
+        let mut program = assemble("ARB #19\nOUT rb-34").unwrap();
         let mut intcode = IntCode { pc: 0, rb: 2000 };
         intcode
             .execute(&mut program, &mut None)
@@ -359,7 +444,7 @@ mod tests {
     #[test]
     fn reentrant_input() {
         // on day 2019-19, the input instruction wrote out of bounds, consuming the input and losing it.
-        let mut program = vec![3, 100, 4, 100, 99];
+        let mut program = assemble("IN -> [100]\nOUT [100]\nHLT").unwrap();
         let mut intcode = IntCode::default();
         let mut input = Some(100);
         let Err(IntCodeErr::OutOfBounds(100)) = intcode.execute(&mut program, &mut input) else {
@@ -391,7 +476,16 @@ mod tests {
         writeln!(ascii, "Hello, world!").unwrap();
 
         // program that loops and reads input til newline (ASCII decimal 10) past end of program.
-        let mut program = vec![109, 13, 109, 1, 203, 0, 2108, 10, 0, 11, 1106, 0, 2, 99];
+        let mut program = assemble(
+            "    ARB #13
+             next:
+                 ARB #1
+                 IN -> rb
+                 EQ #10, rb -> [11] ; the condition of the jump below
+                 JZ #0, #next
+                 HLT",
+        )
+        .unwrap();
         let prog_end = program.len();
         program.resize_with(prog_end + ascii.len(), i64::default);
 
@@ -0,0 +1,164 @@
+//! Line based I/O for programs that talk in ASCII, like the vacuum robot of day 17 and the springdroid
+//! of day 21. Outputs that aren't ASCII, usually the answer, are kept apart from the text.
+//!
+//! # Examples
+//!
+//! ```
+//! use y2019::intcode::{ascii::AsciiMachine, asm::assemble};
+//!
+//! // prints `?\n`, reads a character, then prints it back followed by 1000.
+//! let program = assemble("OUT #63\nOUT #10\nIN -> [c]\nOUT [c]\nOUT #1000\nHLT\nc: DATA 0").unwrap();
+//! let mut machine = AsciiMachine::new(program);
+//! assert_eq!(machine.read_until_prompt(), Ok("?\n".to_string()));
+//! machine.send_line("x");
+//! assert_eq!(machine.read_until_prompt(), Ok("x".to_string()));
+//! assert!(machine.halted());
+//! assert_eq!(machine.values(), [1000]);
+//! ```
+
+use std::{
+    collections::VecDeque,
+    io::{self, BufRead, Write},
+};
+
+use aoc_shared::{parse_to_flat2d, FlatVec2D};
+
+use super::{IntCodeErr, Machine, Status};
+
+#[derive(Clone, Debug, Default)]
+pub struct AsciiMachine {
+    machine: Machine,
+    input: VecDeque<u8>,
+    values: Vec<i64>,
+    halted: bool,
+}
+
+impl From<Machine> for AsciiMachine {
+    fn from(machine: Machine) -> Self {
+        AsciiMachine {
+            machine,
+            ..Default::default()
+        }
+    }
+}
+
+impl AsciiMachine {
+    pub fn new(program: Vec<i64>) -> Self {
+        Machine::new(program).into()
+    }
+
+    /// Queue `line` and a newline as input.
+    pub fn send_line(&mut self, line: &str) {
+        self.input.extend(line.bytes().chain([b'\n']));
+    }
+
+    /// Every output so far that isn't ASCII.
+    pub fn values(&self) -> &[i64] {
+        &self.values
+    }
+
+    pub fn halted(&self) -> bool {
+        self.halted
+    }
+
+    /// Run until the program waits on input or halts, returning the text printed since the last read.
+    pub fn read_until_prompt(&mut self) -> Result<String, IntCodeErr> {
+        let mut text = String::new();
+        loop {
+            match self.machine.run_until_output(&mut self.input)? {
+                Status::Output(out) => match u8::try_from(out) {
+                    Ok(chr) if chr.is_ascii() => text.push(chr.into()),
+                    _ => self.values.push(out),
+                },
+                Status::NeedInput => return Ok(text),
+                Status::Halted => {
+                    self.halted = true;
+                    return Ok(text);
+                }
+            }
+        }
+    }
+
+    /// [`AsciiMachine::read_until_prompt`], keeping only the picture printed before the first blank
+    /// line, e.g. a camera view. Short rows are padded with 0.
+    pub fn read_view(&mut self) -> Result<FlatVec2D<u8>, IntCodeErr> {
+        let text = self.read_until_prompt()?;
+        let picture = text.split("\n\n").next().unwrap_or_default();
+        Ok(parse_to_flat2d(picture.as_bytes()))
+    }
+
+    /// Play the program with `input` as the keyboard, e.g. a text adventure on stdin, until it halts
+    /// or `input` ends. Values that aren't ASCII are shown on their own line.
+    pub fn interact(&mut self, mut input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+        loop {
+            let seen = self.values.len();
+            let text = self.read_until_prompt().map_err(io::Error::other)?;
+            output.write_all(text.as_bytes())?;
+            for value in &self.values[seen..] {
+                writeln!(output, "{value}")?;
+            }
+            output.flush()?;
+
+            let mut line = String::new();
+            if self.halted || input.read_line(&mut line)? == 0 {
+                return Ok(());
+            }
+            self.send_line(line.trim_end_matches(['\n', '\r']));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::AsciiMachine;
+    use crate::intcode::asm::assemble;
+
+    #[test]
+    fn view_and_interact() {
+        // a camera view, then echoes lines back upper cased until it reads an empty one.
+        let program = assemble(
+            "    OUT #35
+                 OUT #46
+                 OUT #10
+                 OUT #94
+                 OUT #10
+                 OUT #10
+             loop:
+                 OUT #62
+                 IN -> [c]
+                 EQ [c], #10 -> [t]
+                 JNZ [t], #end
+             echo:
+                 LT [c], #97 -> [t]
+                 JNZ [t], #print
+                 ADD [c], #-32 -> [c]
+             print:
+                 OUT [c]
+                 IN -> [c]
+                 EQ [c], #10 -> [t]
+                 JZ [t], #echo
+                 OUT #10
+                 JNZ #1, #loop
+             end:
+                 OUT #-1
+                 HLT
+             c:  DATA 0
+             t:  DATA 0",
+        )
+        .unwrap();
+
+        let mut machine = AsciiMachine::new(program.clone());
+        let view = machine.read_view().unwrap();
+        assert_eq!((view.1, view.2), (2, 2));
+        assert_eq!(view.0, b"#.^\0");
+
+        let mut screen = vec![];
+        AsciiMachine::new(program)
+            .interact(&b"go north\r\n\n"[..], &mut screen)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(screen).unwrap(),
+            "#.\n^\n\n>GO NORTH\n>-1\n"
+        );
+    }
+}
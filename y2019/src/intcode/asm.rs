@@ -0,0 +1,259 @@
+//! Assembler for the syntax [`disasm`](super::disasm) prints, for writing readable test programs.
+//!
+//! ```text
+//! start:                  ; a label, usable wherever a number is
+//!     IN -> [n]           ; [x] is position, #x immediate and rb+x relative mode
+//!     ADD [n], #-1 -> [n] ; the written parameter comes after `->`
+//!     JNZ [n], #start
+//!     HLT
+//! n:  DATA 0, start       ; raw cells
+//! ```
+//!
+//! Mnemonics and `DATA` are case insensitive, labels are letters and digits, `;` starts a comment.
+
+use std::collections::{hash_map::Entry, HashMap};
+
+use aoc_shared::{
+    parse::{expect, number, Cursor, ParseError, ParseResult, Parser},
+    Token,
+};
+
+use super::{encode, Op, PMode};
+
+enum Value<'a> {
+    Num(i64),
+    Label { name: &'a [u8], offset: usize },
+}
+
+fn value<'a>(cursor: &mut Cursor<'a>) -> ParseResult<Value<'a>> {
+    let start = cursor.offset();
+    match cursor.peek() {
+        Token::Something(name) if name[0].is_ascii_alphabetic() => {
+            cursor.next_token();
+            Ok(Value::Label {
+                name,
+                offset: start,
+            })
+        }
+        _ => number()
+            .parse(cursor)
+            .map(Value::Num)
+            .map_err(|_| cursor.error_at(start, "a number or label")),
+    }
+}
+
+fn param<'a>(cursor: &mut Cursor<'a>) -> ParseResult<(PMode, Value<'a>)> {
+    match cursor.peek() {
+        Token::Delimiter(b'[') => {
+            cursor.next_token();
+            let addr = value(cursor)?;
+            expect("]").parse(cursor)?;
+            Ok((PMode::Pos, addr))
+        }
+        Token::Delimiter(b'#') => {
+            cursor.next_token();
+            Ok((PMode::Imm, value(cursor)?))
+        }
+        Token::Something(b"rb") => {
+            cursor.next_token();
+            let mut ahead = cursor.clone();
+            // a bare `rb` is `rb+0`.
+            let offset = match number().parse(&mut ahead) {
+                Ok(offset) => {
+                    *cursor = ahead;
+                    offset
+                }
+                Err(_) => 0,
+            };
+            Ok((PMode::Rel, Value::Num(offset)))
+        }
+        _ => Err(cursor.error("a parameter, `[x]`, `#x` or `rb+x`")),
+    }
+}
+
+#[derive(Default)]
+struct Assembler<'a> {
+    program: Vec<i64>,
+    labels: HashMap<&'a [u8], usize>,
+    /// Cells to fill in with the address of a label once every label is known.
+    fixups: Vec<(usize, &'a [u8], usize)>,
+}
+
+impl<'a> Assembler<'a> {
+    fn emit(&mut self, value: Value<'a>) {
+        match value {
+            Value::Num(num) => self.program.push(num),
+            Value::Label { name, offset } => {
+                self.fixups.push((self.program.len(), name, offset));
+                self.program.push(0);
+            }
+        }
+    }
+
+    /// The rest of an instruction or `DATA` line, after its first word.
+    fn statement(
+        &mut self,
+        cursor: &mut Cursor<'a>,
+        name: &[u8],
+        offset: usize,
+    ) -> ParseResult<()> {
+        if name.eq_ignore_ascii_case(b"DATA") {
+            let mut first = true;
+            while !matches!(
+                cursor.peek(),
+                Token::Newline | Token::DoubleNewline | Token::End
+            ) {
+                if !first {
+                    expect(",").parse(cursor)?;
+                }
+                first = false;
+                let value = value(cursor)?;
+                self.emit(value);
+            }
+            return Ok(());
+        }
+
+        let op = Op::ALL
+            .into_iter()
+            .find(|op| op.mnemonic().as_bytes().eq_ignore_ascii_case(name))
+            .ok_or_else(|| cursor.error_at(offset, "a mnemonic or `DATA`"))?;
+        let mut params = vec![];
+        for i in 0..op.arity() - usize::from(op.writes()) {
+            if i > 0 {
+                expect(",").parse(cursor)?;
+            }
+            params.push(param(cursor)?);
+        }
+        if op.writes() {
+            expect("->").parse(cursor)?;
+            let start = cursor.offset();
+            let write = param(cursor)?;
+            if write.0 == PMode::Imm {
+                return Err(cursor.error_at(start, "a position or relative parameter"));
+            }
+            params.push(write);
+        }
+        let modes = params.iter().map(|&(mode, _)| mode).collect::<Vec<_>>();
+        self.program.push(encode(op, &modes));
+        params.into_iter().for_each(|(_, value)| self.emit(value));
+        Ok(())
+    }
+}
+
+/// Assemble `source` into a program, see the module docs for the syntax.
+///
+/// # Examples
+///
+/// ```
+/// use y2019::intcode::asm::assemble;
+///
+/// let program = assemble("    ADD [12], #5 -> rb+3\nend: JZ #0, #end").unwrap();
+/// assert_eq!(program, vec![21001, 12, 5, 3, 1106, 0, 4]);
+///
+/// let err = assemble("IN -> #3").unwrap_err();
+/// assert_eq!(err.to_string(), "1:7: expected a position or relative parameter, found `#`");
+/// ```
+pub fn assemble(source: &str) -> Result<Vec<i64>, ParseError> {
+    // blank out comments and tabs, so that offsets in errors still point into `source`.
+    let mut comment = false;
+    let source = source
+        .bytes()
+        .map(|chr| {
+            comment = chr != b'\n' && (comment || chr == b';');
+            if comment || chr == b'\t' || chr == b'\r' {
+                b' '
+            } else {
+                chr
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut cursor = Cursor::new(&source);
+    let mut asm = Assembler::default();
+    loop {
+        match cursor.next_token() {
+            (_, Token::Newline | Token::DoubleNewline) => (),
+            (_, Token::End) => break,
+            (offset, Token::Something(name)) if name[0].is_ascii_alphabetic() => {
+                if cursor.peek() == Token::Delimiter(b':') {
+                    cursor.next_token();
+                    match asm.labels.entry(name) {
+                        Entry::Vacant(slot) => _ = slot.insert(asm.program.len()),
+                        Entry::Occupied(_) => {
+                            return Err(cursor.error_at(offset, "a label not defined before"))
+                        }
+                    }
+                    continue;
+                }
+                asm.statement(&mut cursor, name, offset)?;
+                if !matches!(
+                    cursor.peek(),
+                    Token::Newline | Token::DoubleNewline | Token::End
+                ) {
+                    return Err(cursor.error("end of line"));
+                }
+            }
+            (offset, _) => return Err(cursor.error_at(offset, "a label, mnemonic or `DATA`")),
+        }
+    }
+
+    for (cell, name, offset) in asm.fixups {
+        let addr = asm
+            .labels
+            .get(name)
+            .ok_or_else(|| cursor.error_at(offset, "a defined label"))?;
+        asm.program[cell] = *addr as i64;
+    }
+    Ok(asm.program)
+}
+
+#[cfg(test)]
+mod test {
+    use super::assemble;
+    use crate::intcode::disasm::disasm;
+
+    #[test]
+    fn labels_and_data() {
+        let program = assemble(
+            "start:                  ; a label, usable wherever a number is
+                 IN -> [n]           ; [x] is position, #x immediate and rb+x relative mode
+                 add [n], #-1 -> [n]
+                 JNZ [n], #start
+                 HLT
+             n:  DATA 0, start",
+        )
+        .unwrap();
+        assert_eq!(
+            program,
+            vec![3, 10, 1001, 10, -1, 10, 1005, 10, 0, 99, 0, 0]
+        );
+
+        for (source, (line, col)) in [
+            ("  ADD #1, #2", (1, 13)),
+            ("  ADD #1, #2 -> [3], #4", (1, 20)),
+            ("OUT #1\nFOO #1", (2, 1)),
+            ("x: HLT\nx: HLT", (2, 1)),
+            ("JZ #0, #nowhere", (1, 9)),
+            // `rb` is only a register outside of brackets.
+            ("\tOUT\t[rb]", (1, 7)),
+        ] {
+            let err = assemble(source).unwrap_err();
+            assert_eq!((err.line, err.col), (line, col), "{source:?}: {err}");
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        #[rustfmt::skip]
+        let program = [
+            21101, 7, 0, 1,
+            1105, 1, 11,
+            99,
+            42, -7, 1 << 40,
+            1101, 0, 99, 17,
+            2106, 0, 1,
+            1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12,
+        ];
+        assert_eq!(assemble(&disasm(&program).to_string()).unwrap(), program);
+    }
+}
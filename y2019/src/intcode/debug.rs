@@ -0,0 +1,320 @@
+//! Step debugger over [`IntCode::execute`], with breakpoints, watchpoints, tracing and reverse stepping.
+//!
+//! # Examples
+//!
+//! ```
+//! use y2019::intcode::{asm::assemble, debug::{Debugger, Stop}};
+//!
+//! let program = assemble("IN -> [9]\nMUL [9], #2 -> [9]\nOUT [9]\nHLT\nDATA 0").unwrap();
+//! let mut dbg = Debugger::new(program);
+//! dbg.watch(9);
+//! dbg.push_input(21);
+//! assert!(matches!(dbg.run(), Stop::Watchpoint { addr: 9, old: 0, new: 21 }));
+//! assert!(matches!(dbg.run(), Stop::Watchpoint { addr: 9, old: 21, new: 42 }));
+//! assert!(matches!(dbg.run(), Stop::Halted));
+//! assert_eq!(dbg.outputs(), [42]);
+//!
+//! // back to before the multiply.
+//! assert!(dbg.step_back() && dbg.step_back());
+//! assert_eq!((dbg.state().pc, dbg.memory()[9], dbg.outputs().len()), (2, 21, 0));
+//! ```
+
+use std::{
+    collections::{BTreeSet, VecDeque},
+    fmt::Write,
+};
+
+use super::{brk, disasm::Instr, IntCode, IntCodeErr, Oper, PMode};
+
+/// Steps kept for [`Debugger::step_back`].
+const MAX_HISTORY: usize = 1 << 20;
+
+/// Cells per line of [`Debugger::dump`].
+const DUMP_WIDTH: usize = 8;
+
+/// Why the debugger stopped.
+#[derive(Debug)]
+pub enum Stop {
+    /// About to execute the instruction at this address.
+    Breakpoint(usize),
+    /// The last instruction wrote to a watched address.
+    Watchpoint {
+        addr: usize,
+        old: i64,
+        new: i64,
+    },
+    /// Waiting on [`Debugger::push_input`].
+    NeedInput,
+    /// The step limit of [`Debugger::run_for`] was reached.
+    Limit,
+    Halted,
+    Fault(IntCodeErr),
+}
+
+/// Enough to undo one step.
+struct Undo {
+    state: IntCode,
+    len: usize,
+    /// Address written to and what it held, if it was in bounds.
+    write: Option<(usize, i64)>,
+    input: Option<i64>,
+    output: bool,
+}
+
+pub struct Debugger {
+    state: IntCode,
+    memory: Vec<i64>,
+    input: VecDeque<i64>,
+    outputs: Vec<i64>,
+    breakpoints: BTreeSet<usize>,
+    watchpoints: BTreeSet<usize>,
+    trace: Option<Vec<String>>,
+    history: VecDeque<Undo>,
+}
+
+impl Debugger {
+    pub fn new(program: Vec<i64>) -> Self {
+        Debugger {
+            state: IntCode::default(),
+            memory: program,
+            input: VecDeque::new(),
+            outputs: vec![],
+            breakpoints: BTreeSet::new(),
+            watchpoints: BTreeSet::new(),
+            trace: None,
+            history: VecDeque::new(),
+        }
+    }
+
+    /// The `pc` and `rb` registers.
+    pub fn state(&self) -> IntCode {
+        self.state
+    }
+
+    pub fn memory(&self) -> &[i64] {
+        &self.memory
+    }
+
+    /// Everything output so far.
+    pub fn outputs(&self) -> &[i64] {
+        &self.outputs
+    }
+
+    pub fn push_input(&mut self, value: i64) {
+        self.input.push_back(value);
+    }
+
+    /// Queue `line` and a newline as ASCII input.
+    pub fn push_line(&mut self, line: &str) {
+        self.input
+            .extend(line.bytes().chain([b'\n']).map(i64::from));
+    }
+
+    /// Stop before executing the instruction at `addr`.
+    pub fn break_at(&mut self, addr: usize) {
+        self.breakpoints.insert(addr);
+    }
+
+    /// Remove a breakpoint, false if there was none.
+    pub fn clear_break(&mut self, addr: usize) -> bool {
+        self.breakpoints.remove(&addr)
+    }
+
+    /// Stop after any instruction writes to `addr`, even if the value is unchanged.
+    pub fn watch(&mut self, addr: usize) {
+        self.watchpoints.insert(addr);
+    }
+
+    /// Remove a watchpoint, false if there was none.
+    pub fn unwatch(&mut self, addr: usize) -> bool {
+        self.watchpoints.remove(&addr)
+    }
+
+    /// Start or stop logging each executed instruction, see [`Debugger::take_trace`].
+    pub fn set_trace(&mut self, on: bool) {
+        self.trace = on.then(|| self.trace.take().unwrap_or_default());
+    }
+
+    /// Trace lines logged since the last call.
+    pub fn take_trace(&mut self) -> Vec<String> {
+        self.trace.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    /// The instruction at `addr`, with its address, as assembly.
+    pub fn describe(&self, addr: usize) -> String {
+        match Instr::decode(&self.memory, addr) {
+            Some(instr) => format!("{addr:>6}  {instr}"),
+            None => match self.memory.get(addr) {
+                Some(cell) => format!("{addr:>6}  DATA {cell}"),
+                None => format!("{addr:>6}  out of bounds"),
+            },
+        }
+    }
+
+    /// Memory from `start`, `len` cells, with the address of each line.
+    pub fn dump(&self, start: usize, len: usize) -> String {
+        let end = start.saturating_add(len).min(self.memory.len());
+        let mut out = String::new();
+        for line in (start..end).step_by(DUMP_WIDTH) {
+            _ = write!(out, "{line:>6}:");
+            for cell in &self.memory[line..end.min(line + DUMP_WIDTH)] {
+                _ = write!(out, " {cell}");
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Address the instruction at pc writes to, if it writes.
+    fn write_target(&self) -> Option<usize> {
+        let IntCode { pc, rb } = self.state;
+        let Oper { p1, p2, p3, opcode } = Oper::try_from(*self.memory.get(pc)?).ok()?;
+        if !opcode.writes() {
+            return None;
+        }
+        let param = *self.memory.get(pc + opcode.arity())?;
+        match [p1, p2, p3][opcode.arity() - 1] {
+            PMode::Pos => usize::try_from(param).ok(),
+            PMode::Rel => usize::try_from(param.checked_add(rb)?).ok(),
+            PMode::Imm => None,
+        }
+    }
+
+    /// Execute one instruction, growing memory as needed.
+    /// None unless it stopped on a watchpoint, needs input, halted or faulted.
+    pub fn step(&mut self) -> Option<Stop> {
+        let state = self.state;
+        let len = self.memory.len();
+        let target = self.write_target();
+        let write = target.and_then(|addr| Some((addr, *self.memory.get(addr)?)));
+        let input = self.input.front().copied();
+        let queued = self.input.len();
+        let traced = self
+            .trace
+            .is_some()
+            .then(|| format!("{}  ; rb={}", self.describe(state.pc), state.rb));
+
+        let res = loop {
+            match self.state.execute(&mut self.memory, &mut self.input) {
+                Err(IntCodeErr::OutOfBounds(fault)) => {
+                    if let Err(err) = brk(fault, &mut self.memory) {
+                        break Err(err);
+                    }
+                }
+                res => break res,
+            }
+        };
+        let output = match res {
+            Ok(output) => output,
+            Err(err) => {
+                self.memory.truncate(len);
+                return Some(match err {
+                    IntCodeErr::End => Stop::Halted,
+                    IntCodeErr::NeedInput => Stop::NeedInput,
+                    err => Stop::Fault(err),
+                });
+            }
+        };
+
+        if self.history.len() == MAX_HISTORY {
+            self.history.pop_front();
+        }
+        self.history.push_back(Undo {
+            state,
+            len,
+            write,
+            input: input.filter(|_| self.input.len() < queued),
+            output: output.is_some(),
+        });
+        self.outputs.extend(output);
+        if let (Some(trace), Some(line)) = (self.trace.as_mut(), traced) {
+            trace.push(line);
+        }
+
+        let addr = target.filter(|addr| self.watchpoints.contains(addr))?;
+        Some(Stop::Watchpoint {
+            addr,
+            old: write.map_or(0, |(_, old)| old),
+            new: self.memory[addr],
+        })
+    }
+
+    /// Undo the last step, false if there is no history left.
+    pub fn step_back(&mut self) -> bool {
+        let Some(undo) = self.history.pop_back() else {
+            return false;
+        };
+        self.state = undo.state;
+        if let Some((addr, old)) = undo.write {
+            self.memory[addr] = old;
+        }
+        self.memory.truncate(undo.len);
+        if let Some(input) = undo.input {
+            self.input.push_front(input);
+        }
+        if undo.output {
+            self.outputs.pop();
+        }
+        true
+    }
+
+    /// Run until something stops it, see [`Stop`].
+    /// A breakpoint on the current pc is stepped over, so `run` continues after stopping on one.
+    pub fn run(&mut self) -> Stop {
+        self.run_for(usize::MAX)
+    }
+
+    /// [`Debugger::run`] for at most `limit` steps.
+    pub fn run_for(&mut self, limit: usize) -> Stop {
+        for i in 0..limit {
+            let pc = self.state.pc;
+            if i > 0 && self.breakpoints.contains(&pc) {
+                return Stop::Breakpoint(pc);
+            }
+            if let Some(stop) = self.step() {
+                return stop;
+            }
+        }
+        Stop::Limit
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Debugger, Stop};
+    use crate::intcode::asm::assemble;
+
+    #[test]
+    fn breakpoints_and_history() {
+        let program = assemble(
+            "    ARB #1
+             next:
+                 ADD [100], #1 -> [100]
+                 OUT [100]
+                 JNZ #1, #next",
+        )
+        .unwrap();
+        let mut dbg = Debugger::new(program);
+        dbg.break_at(6);
+        assert!(matches!(dbg.run(), Stop::Breakpoint(6)));
+        assert_eq!((dbg.memory().len(), dbg.memory()[100]), (101, 1));
+        assert!(matches!(dbg.run(), Stop::Breakpoint(6)));
+        assert_eq!(dbg.outputs(), [1]);
+
+        dbg.set_trace(true);
+        assert!(matches!(dbg.run_for(2), Stop::Limit));
+        assert_eq!(
+            dbg.take_trace(),
+            ["     6  OUT [100]  ; rb=1", "     8  JNZ #1, #2  ; rb=1"]
+        );
+        assert_eq!(dbg.dump(99, 5), "    99: 0 2\n");
+
+        let mut steps = 0;
+        while dbg.step_back() {
+            steps += 1;
+        }
+        assert_eq!(steps, 7);
+        assert_eq!(dbg.state().pc, 0);
+        assert_eq!((dbg.memory().len(), dbg.outputs()), (11, &[][..]));
+    }
+}
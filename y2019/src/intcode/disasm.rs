@@ -0,0 +1,331 @@
+//! Static disassembly of intcode programs, for reverse-engineering them.
+//!
+//! Code is whatever is reachable from the entry points: falling through, jumps with an immediate target,
+//! and return addresses, an immediate stored just before an unconditional jump as in
+//! `ADD #ret, #0 -> rb+1; JNZ #1, #func`. Everything else is data.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    fmt::{self, Write},
+};
+
+use super::{encode, Op, Oper, PMode};
+
+/// A parameter as it appears in the program.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Param {
+    pub mode: PMode,
+    pub value: i64,
+}
+
+impl fmt::Display for Param {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.mode {
+            PMode::Pos => write!(f, "[{}]", self.value),
+            PMode::Imm => write!(f, "#{}", self.value),
+            PMode::Rel => write!(f, "rb{:+}", self.value),
+        }
+    }
+}
+
+/// Value of an immediate mode parameter.
+fn imm(param: Option<&Param>) -> Option<i64> {
+    param
+        .filter(|param| param.mode == PMode::Imm)
+        .map(|param| param.value)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Instr {
+    pub addr: usize,
+    pub op: Op,
+    pub params: Vec<Param>,
+    /// Start of the instruction this one writes into, for position mode writes.
+    pub modifies: Option<usize>,
+}
+
+impl Instr {
+    /// Decode the instruction at `addr`.
+    /// None if it is invalid, runs past the end, or isn't encoded the way the assembler would.
+    pub fn decode(program: &[i64], addr: usize) -> Option<Instr> {
+        let &cell = program.get(addr)?;
+        let Oper { p1, p2, p3, opcode } = Oper::try_from(cell).ok()?;
+        let modes = &[p1, p2, p3][..opcode.arity()];
+        if encode(opcode, modes) != cell || opcode.writes() && modes.last() == Some(&PMode::Imm) {
+            return None;
+        }
+        let params = modes
+            .iter()
+            .zip(program.get(addr + 1..addr + 1 + modes.len())?)
+            .map(|(&mode, &value)| Param { mode, value })
+            .collect();
+        Some(Instr {
+            addr,
+            op: opcode,
+            params,
+            modifies: None,
+        })
+    }
+
+    /// Number of cells taken up.
+    pub fn width(&self) -> usize {
+        1 + self.params.len()
+    }
+
+    /// Target of a jump, if it is immediate.
+    pub fn jump_target(&self) -> Option<usize> {
+        match self.op {
+            Op::Jit | Op::Jif => usize::try_from(imm(self.params.get(1))?).ok(),
+            _ => None,
+        }
+    }
+
+    /// True for jumps with an immediate condition that always holds.
+    pub fn always_jumps(&self) -> bool {
+        match (self.op, imm(self.params.first())) {
+            (Op::Jit, Some(cond)) => cond != 0,
+            (Op::Jif, Some(cond)) => cond == 0,
+            _ => false,
+        }
+    }
+
+    /// True if execution can continue with the next instruction.
+    pub fn falls_through(&self) -> bool {
+        self.op != Op::End && !self.always_jumps()
+    }
+
+    /// Address written to, if it is known without running the program.
+    pub fn write_addr(&self) -> Option<usize> {
+        match self.params.last() {
+            Some(&Param {
+                mode: PMode::Pos,
+                value,
+            }) if self.op.writes() => usize::try_from(value).ok(),
+            _ => None,
+        }
+    }
+
+    /// Value stored by an `ADD` or `MUL` of two immediates.
+    fn constant_store(&self) -> Option<i64> {
+        let a = imm(self.params.first())?;
+        let b = imm(self.params.get(1))?;
+        match self.op {
+            Op::Add => a.checked_add(b),
+            Op::Mul => a.checked_mul(b),
+            _ => None,
+        }
+    }
+
+    /// Write as assembly, with immediate jump targets in `labels` as `#L<addr>`.
+    fn write(&self, out: &mut impl Write, labels: &BTreeSet<usize>) -> fmt::Result {
+        out.write_str(self.op.mnemonic())?;
+        let (reads, write) = match self.params.split_last() {
+            Some((write, reads)) if self.op.writes() => (reads, Some(write)),
+            _ => (&self.params[..], None),
+        };
+        for (i, param) in reads.iter().enumerate() {
+            out.write_str(if i == 0 { " " } else { ", " })?;
+            match self.jump_target() {
+                Some(target) if i == 1 && labels.contains(&target) => write!(out, "#L{target}")?,
+                _ => write!(out, "{param}")?,
+            }
+        }
+        match write {
+            Some(write) => write!(out, " -> {write}"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for Instr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, &BTreeSet::new())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Item {
+    Code(Instr),
+    /// Cells never reached as code.
+    Data {
+        addr: usize,
+        values: Vec<i64>,
+    },
+}
+
+/// A whole program, see [`disasm`]. Displays as assembly with the address of each line in a comment.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Listing {
+    /// Every cell of the program, in order.
+    pub items: Vec<Item>,
+    /// Jump targets and return addresses.
+    pub labels: BTreeSet<usize>,
+}
+
+/// Values per `DATA` line.
+const DATA_WIDTH: usize = 8;
+
+impl fmt::Display for Listing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for item in &self.items {
+            match item {
+                Item::Code(instr) => {
+                    if self.labels.contains(&instr.addr) {
+                        writeln!(f, "L{}:", instr.addr)?;
+                    }
+                    let mut line = String::new();
+                    instr.write(&mut line, &self.labels)?;
+                    match instr.modifies {
+                        Some(target) => writeln!(
+                            f,
+                            "    {line:<31} ; {} modifies code at {target}",
+                            instr.addr
+                        )?,
+                        None => writeln!(f, "    {line:<31} ; {}", instr.addr)?,
+                    }
+                }
+                Item::Data { addr, values } => {
+                    for (i, chunk) in values.chunks(DATA_WIDTH).enumerate() {
+                        let line = chunk
+                            .iter()
+                            .map(i64::to_string)
+                            .collect::<Vec<_>>()
+                            .join(", ");
+                        writeln!(f, "    DATA {line:<26} ; {}", addr + i * DATA_WIDTH)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Disassemble `program`, starting from pc 0.
+///
+/// # Examples
+///
+/// ```
+/// use y2019::intcode::disasm::disasm;
+///
+/// let listing = disasm(&[21001, 12, 5, 3, 99]);
+/// assert_eq!(listing.to_string().lines().next(), Some("    ADD [12], #5 -> rb+3            ; 0"));
+/// ```
+pub fn disasm(program: &[i64]) -> Listing {
+    disasm_from(program, [0])
+}
+
+/// [`disasm`] from several entry points, e.g. for code only reached through computed jumps.
+pub fn disasm_from(program: &[i64], entries: impl IntoIterator<Item = usize>) -> Listing {
+    let mut code = BTreeMap::new();
+    // start of the instruction covering each cell.
+    let mut owner = vec![None; program.len()];
+    let mut labels = BTreeSet::new();
+    let mut queue = entries.into_iter().collect::<VecDeque<_>>();
+
+    while let Some(mut pc) = queue.pop_front() {
+        let mut prev_store = None;
+        while let Some(instr) = Instr::decode(program, pc) {
+            let cells = &mut owner[pc..pc + instr.width()];
+            // already walked, or overlapping other code.
+            if cells.iter().any(Option::is_some) {
+                break;
+            }
+            cells.fill(Some(pc));
+            if let Some(target) = instr.jump_target() {
+                labels.insert(target);
+                queue.push_back(target);
+                if let Some(ret) = prev_store
+                    .and_then(|ret| usize::try_from(ret).ok())
+                    .filter(|&ret| instr.always_jumps() && ret > pc)
+                {
+                    labels.insert(ret);
+                    queue.push_back(ret);
+                }
+            }
+            prev_store = instr.constant_store();
+            let next = pc + instr.width();
+            let falls_through = instr.falls_through();
+            code.insert(pc, instr);
+            if !falls_through {
+                break;
+            }
+            pc = next;
+        }
+    }
+
+    labels.retain(|addr| code.contains_key(addr));
+    for instr in code.values_mut() {
+        instr.modifies = instr
+            .write_addr()
+            .and_then(|addr| owner.get(addr).copied().flatten());
+    }
+
+    let mut items = vec![];
+    let mut addr = 0;
+    while addr < program.len() {
+        if let Some(instr) = code.remove(&addr) {
+            addr += instr.width();
+            items.push(Item::Code(instr));
+        } else {
+            let end = (addr..program.len())
+                .find(|&cell| owner[cell].is_some())
+                .unwrap_or(program.len());
+            items.push(Item::Data {
+                addr,
+                values: program[addr..end].to_vec(),
+            });
+            addr = end;
+        }
+    }
+    Listing { items, labels }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{disasm, disasm_from, Item};
+
+    #[test]
+    fn reachability() {
+        #[rustfmt::skip]
+        let program = [
+            21101, 7, 0, 1, // call, returning to 7
+            1105, 1, 9,
+            99,
+            42,
+            1101, 0, 99, 15, // rewrites the jump below
+            2106, 0, 1, // return
+        ];
+        let listing = disasm(&program);
+        assert_eq!(
+            listing.to_string(),
+            "    ADD #7, #0 -> rb+1              ; 0\n\
+             \x20   JNZ #1, #L9                     ; 4\n\
+             L7:\n\
+             \x20   HLT                             ; 7\n\
+             \x20   DATA 42                         ; 8\n\
+             L9:\n\
+             \x20   ADD #0, #99 -> [15]             ; 9 modifies code at 13\n\
+             \x20   JZ #0, rb+1                     ; 13\n"
+        );
+
+        // without the call, the HLT after the jump is never reached.
+        let listing = disasm(&program[4..]);
+        assert!(matches!(listing.items[1], Item::Data { addr: 3, .. }));
+        let listing = disasm_from(&program[4..], [0, 3]);
+        assert!(matches!(listing.items[1], Item::Code(_)));
+    }
+
+    #[test]
+    fn data() {
+        // invalid opcode, mode on an unused parameter, and an immediate write.
+        for program in [[7777, 0, 0, 0], [10004, 0, 0, 0], [11101, 0, 0, 0]] {
+            assert_eq!(
+                disasm(&program).items,
+                vec![Item::Data {
+                    addr: 0,
+                    values: program.to_vec()
+                }]
+            );
+        }
+    }
+}
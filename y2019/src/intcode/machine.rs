@@ -0,0 +1,146 @@
+use super::{brk, Input, IntCode, IntCodeErr, Output};
+
+/// Why a [`Machine`] stopped.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Status {
+    Output(i64),
+    /// The input ran out, give it more and run again.
+    NeedInput,
+    Halted,
+}
+
+/// An [`IntCode`] machine that owns its memory and grows it on out of bounds accesses,
+/// up to the same 4GiB limit as [`brk`].
+/// Cloning takes a snapshot, e.g. to branch a search from the current state.
+#[derive(Clone, Debug, Default)]
+pub struct Machine {
+    state: IntCode,
+    memory: Vec<i64>,
+}
+
+impl Machine {
+    pub fn new(program: Vec<i64>) -> Self {
+        Machine {
+            state: IntCode::default(),
+            memory: program,
+        }
+    }
+
+    /// The `pc` and `rb` registers.
+    pub fn state(&self) -> IntCode {
+        self.state
+    }
+
+    pub fn memory(&self) -> &[i64] {
+        &self.memory
+    }
+
+    /// Memory at `addr`, 0 if it hasn't been grown that far yet.
+    pub fn peek(&self, addr: usize) -> i64 {
+        self.memory.get(addr).copied().unwrap_or_default()
+    }
+
+    /// Set memory at `addr`, growing memory if needed.
+    pub fn poke(&mut self, addr: usize, value: i64) -> Result<(), IntCodeErr> {
+        if addr >= self.memory.len() {
+            brk(addr, &mut self.memory)?;
+        }
+        self.memory[addr] = value;
+        Ok(())
+    }
+
+    /// Execute one instruction, see [`IntCode::execute`].
+    pub fn step<I: Input>(&mut self, input: &mut I) -> Result<Option<i64>, IntCodeErr> {
+        loop {
+            match self.state.execute(&mut self.memory, input) {
+                Err(IntCodeErr::OutOfBounds(fault)) => brk(fault, &mut self.memory)?,
+                res => return res,
+            }
+        }
+    }
+
+    /// Run until the next output, or until the machine needs input or halts.
+    pub fn run_until_output<I: Input>(&mut self, input: &mut I) -> Result<Status, IntCodeErr> {
+        loop {
+            match self.step(input) {
+                Ok(None) => (),
+                Ok(Some(out)) => return Ok(Status::Output(out)),
+                Err(IntCodeErr::NeedInput) => return Ok(Status::NeedInput),
+                Err(IntCodeErr::End) => return Ok(Status::Halted),
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    /// Run until the machine needs input or halts, collecting every output along the way.
+    pub fn run_until_input<I: Input>(
+        &mut self,
+        input: &mut I,
+        outputs: &mut impl Output,
+    ) -> Result<Status, IntCodeErr> {
+        loop {
+            match self.run_until_output(input)? {
+                Status::Output(out) => outputs.output(out),
+                status => return Ok(status),
+            }
+        }
+    }
+
+    /// Run until the machine halts, collecting every output; running out of input is an error.
+    pub fn run_until_halt<I: Input>(
+        &mut self,
+        input: &mut I,
+        outputs: &mut impl Output,
+    ) -> Result<(), IntCodeErr> {
+        match self.run_until_input(input, outputs)? {
+            Status::NeedInput => Err(IntCodeErr::NeedInput),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::VecDeque;
+
+    use super::{Machine, Status};
+    use crate::intcode::{asm::assemble, IntCodeErr};
+
+    #[test]
+    fn grows_and_snapshots() {
+        let program = assemble(
+            "next:
+                 IN -> [100]
+                 ADD [100], [200] -> [200]
+                 OUT [200]
+                 JNZ #1, #next",
+        )
+        .unwrap();
+        let mut machine = Machine::new(program);
+        assert_eq!(
+            machine.run_until_output(&mut Some(3)),
+            Ok(Status::Output(3))
+        );
+        assert_eq!(machine.memory().len(), 201);
+
+        let mut branch = machine.clone();
+        assert_eq!(branch.run_until_output(&mut Some(4)), Ok(Status::Output(7)));
+        assert_eq!(
+            machine.run_until_output(&mut Some(5)),
+            Ok(Status::Output(8))
+        );
+
+        let mut outputs = vec![];
+        let mut input = VecDeque::from([1, 1]);
+        assert_eq!(
+            machine.run_until_input(&mut input, &mut outputs),
+            Ok(Status::NeedInput)
+        );
+        assert_eq!(outputs, [9, 10]);
+        assert_eq!(
+            machine.run_until_halt(&mut None, &mut outputs),
+            Err(IntCodeErr::NeedInput)
+        );
+        assert_eq!((machine.peek(200), machine.peek(1 << 20)), (10, 0));
+    }
+}
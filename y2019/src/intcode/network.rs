@@ -0,0 +1,342 @@
+//! Machines that talk to each other: over channels, each on its own thread, or as a packet switched
+//! [`Network`] as in 2019 day 23.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::thread;
+//!
+//! use y2019::intcode::{asm::assemble, network::channel, Machine};
+//!
+//! // doubles its input, forever.
+//! let program = assemble("loop: IN -> [9]\nMUL [9], #2 -> [9]\nOUT [9]\nJNZ #1, #loop\nDATA 0").unwrap();
+//! let (mut tx, rx) = channel();
+//! let (mut out, mut results) = channel();
+//! let mut machine = Machine::new(program);
+//! let doubler = thread::spawn(move || machine.run_until_halt(&mut rx.blocking(), &mut out));
+//!
+//! tx.send(21).unwrap();
+//! assert_eq!(results.blocking_recv(), Some(42));
+//! // hanging up makes the machine fail on its next input.
+//! drop(tx);
+//! assert!(doubler.join().unwrap().is_err());
+//! ```
+
+use std::{
+    cell::Cell,
+    collections::VecDeque,
+    ops::ControlFlow,
+    panic,
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+};
+
+use super::{Input, IntCodeErr, Machine, Status};
+
+/// Receiving end of [`channel`], usable as [`Input`].
+/// Polling by default: a machine reading from an empty channel stops with [`Status::NeedInput`].
+#[derive(Debug)]
+pub struct ChannelInput {
+    rx: Receiver<i64>,
+    /// Received but not yet consumed.
+    next: Cell<Option<i64>>,
+    blocking: bool,
+}
+
+/// A channel whose sending end is an [`Output`](super::Output) and whose receiving end is an [`Input`].
+pub fn channel() -> (Sender<i64>, ChannelInput) {
+    let (tx, rx) = mpsc::channel();
+    let input = ChannelInput {
+        rx,
+        next: Cell::new(None),
+        blocking: false,
+    };
+    (tx, input)
+}
+
+impl ChannelInput {
+    /// Wait for input instead, until every sender hangs up.
+    pub fn blocking(self) -> Self {
+        ChannelInput {
+            blocking: true,
+            ..self
+        }
+    }
+
+    /// Wait for the next value, even if polling; None once every sender hung up.
+    pub fn blocking_recv(&mut self) -> Option<i64> {
+        self.next.take().or_else(|| self.rx.recv().ok())
+    }
+}
+
+impl Input for ChannelInput {
+    fn peek(&self) -> Option<i64> {
+        if self.next.get().is_none() {
+            self.next.set(if self.blocking {
+                self.rx.recv().ok()
+            } else {
+                self.rx.try_recv().ok()
+            });
+        }
+        self.next.get()
+    }
+
+    fn consume(&mut self) {
+        self.next.set(None)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Packet {
+    pub addr: i64,
+    pub payload: Vec<i64>,
+}
+
+/// Sees every packet addressed outside a [`Network`], and wakes the network up when it goes idle.
+pub trait Nat {
+    type Output;
+
+    /// A packet to an address outside the network. Break to stop the network.
+    fn receive(&mut self, packet: Packet) -> ControlFlow<Self::Output>;
+
+    /// Every machine is waiting on an empty inbox. Break to stop the network, or continue with a
+    /// packet to send, routed like any other.
+    fn idle(&mut self) -> ControlFlow<Self::Output, Packet>;
+}
+
+/// A machine of a [`Network`], with what it hasn't read yet and what hasn't been routed yet.
+#[derive(Clone, Debug)]
+struct Node {
+    machine: Machine,
+    inbox: VecDeque<i64>,
+    outbox: Vec<i64>,
+    halted: bool,
+}
+
+impl Node {
+    /// Run until it waits on input again; true if it had nothing to read and sent nothing.
+    fn turn(&mut self, idle_input: i64) -> Result<bool, IntCodeErr> {
+        let starved = self.inbox.is_empty();
+        if starved {
+            self.inbox.push_back(idle_input);
+        }
+        let sent = self.outbox.len();
+        if self
+            .machine
+            .run_until_input(&mut self.inbox, &mut self.outbox)?
+            == Status::Halted
+        {
+            // nobody is listening anymore.
+            self.inbox.clear();
+            self.halted = true;
+        }
+        Ok(starved && self.outbox.len() == sent)
+    }
+}
+
+/// Turns for a share of the nodes of a round; true if all of them were idle.
+fn turns(nodes: &mut [Node], idle_input: i64) -> Result<bool, IntCodeErr> {
+    nodes
+        .iter_mut()
+        .try_fold(true, |idle, node| Ok(node.turn(idle_input)? && idle))
+}
+
+/// Machines sending each other packets of an address followed by a payload.
+///
+/// Runs in rounds: every machine runs until it waits on input, then the packets they sent are routed.
+/// A machine waiting on an empty inbox is given the idle input instead, and the network is idle when
+/// a round passes in which every machine did so and nobody sent anything.
+#[derive(Clone, Debug)]
+pub struct Network {
+    nodes: Vec<Node>,
+    packet_len: usize,
+    idle_input: i64,
+    threads: usize,
+}
+
+impl Network {
+    /// `size` copies of `program`, each first given its address.
+    pub fn new(program: &[i64], size: usize) -> Self {
+        let nodes = (0..size)
+            .map(|addr| Node {
+                machine: Machine::new(program.to_vec()),
+                inbox: VecDeque::from([addr as i64]),
+                outbox: vec![],
+                halted: false,
+            })
+            .collect();
+        Network {
+            nodes,
+            packet_len: 2,
+            idle_input: -1,
+            threads: 1,
+        }
+    }
+
+    /// Values in a payload, 2 by default.
+    pub fn packet_len(self, packet_len: usize) -> Self {
+        Network { packet_len, ..self }
+    }
+
+    /// What a machine waiting on an empty inbox reads, -1 by default.
+    pub fn idle_input(self, idle_input: i64) -> Self {
+        Network { idle_input, ..self }
+    }
+
+    /// Run the machines of each round on up to `threads` threads, 1 by default.
+    /// Packets are only routed between rounds, so this doesn't change the results.
+    pub fn threads(self, threads: usize) -> Self {
+        Network {
+            threads: threads.max(1),
+            ..self
+        }
+    }
+
+    /// Queue `packet` for its machine, or give it back if it is addressed outside the network.
+    pub fn send(&mut self, packet: Packet) -> Option<Packet> {
+        match usize::try_from(packet.addr)
+            .ok()
+            .and_then(|addr| self.nodes.get_mut(addr))
+        {
+            Some(node) => {
+                node.inbox.extend(packet.payload);
+                None
+            }
+            None => Some(packet),
+        }
+    }
+
+    /// Give every machine a turn, then route what they sent.
+    /// Returns whether the network was idle, and the packets addressed outside it.
+    fn round(&mut self) -> Result<(bool, Vec<Packet>), IntCodeErr> {
+        let idle_input = self.idle_input;
+        let idle = if self.threads == 1 {
+            turns(&mut self.nodes, idle_input)?
+        } else {
+            let share = self.nodes.len().div_ceil(self.threads).max(1);
+            thread::scope(|scope| {
+                let handles = self
+                    .nodes
+                    .chunks_mut(share)
+                    .map(|nodes| scope.spawn(move || turns(nodes, idle_input)))
+                    .collect::<Vec<_>>();
+                handles.into_iter().try_fold(true, |idle, handle| {
+                    let share_idle = handle
+                        .join()
+                        .unwrap_or_else(|err| panic::resume_unwind(err))?;
+                    Ok(share_idle && idle)
+                })
+            })?
+        };
+
+        let width = self.packet_len + 1;
+        let mut outside = vec![];
+        for i in 0..self.nodes.len() {
+            let outbox = &mut self.nodes[i].outbox;
+            // a packet split over two turns waits for its end.
+            let sent = outbox
+                .drain(..outbox.len() / width * width)
+                .collect::<Vec<_>>();
+            for packet in sent.chunks(width) {
+                outside.extend(self.send(Packet {
+                    addr: packet[0],
+                    payload: packet[1..].to_vec(),
+                }));
+            }
+        }
+        Ok((idle, outside))
+    }
+
+    /// Run until `nat` stops it. Fails with [`IntCodeErr::End`] if every machine halts first.
+    pub fn run<N: Nat>(&mut self, nat: &mut N) -> Result<N::Output, IntCodeErr> {
+        loop {
+            let (idle, outside) = self.round()?;
+            for packet in outside {
+                if let ControlFlow::Break(out) = nat.receive(packet) {
+                    return Ok(out);
+                }
+            }
+            if self.nodes.iter().all(|node| node.halted) {
+                return Err(IntCodeErr::End);
+            }
+            if idle {
+                let packet = match nat.idle() {
+                    ControlFlow::Break(out) => return Ok(out),
+                    ControlFlow::Continue(packet) => packet,
+                };
+                if let Some(packet) = self.send(packet) {
+                    if let ControlFlow::Break(out) = nat.receive(packet) {
+                        return Ok(out);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::ops::ControlFlow;
+
+    use super::{Nat, Network, Packet};
+    use crate::intcode::{asm::assemble, IntCodeErr};
+
+    /// Starts a packet off at 0 and stops when it comes out the other end.
+    struct Start(bool);
+
+    impl Nat for Start {
+        type Output = Packet;
+
+        fn receive(&mut self, packet: Packet) -> ControlFlow<Packet> {
+            ControlFlow::Break(packet)
+        }
+
+        fn idle(&mut self) -> ControlFlow<Packet, Packet> {
+            assert!(!self.0, "idle twice");
+            self.0 = true;
+            ControlFlow::Continue(Packet {
+                addr: 0,
+                payload: vec![7, 0],
+            })
+        }
+    }
+
+    #[test]
+    fn relay() {
+        // passes packets on to the next address, counting hops in y.
+        let program = assemble(
+            "    IN -> [me]
+                 ADD [me], #1 -> [next]
+             loop:
+                 IN -> [x]
+                 EQ [x], #-1 -> [t]
+                 JNZ [t], #loop
+                 IN -> [y]
+                 ADD [y], #1 -> [y]
+                 OUT [next]
+                 OUT [x]
+                 OUT [y]
+                 JNZ #1, #loop
+             me: DATA 0
+             next: DATA 0
+             x:  DATA 0
+             y:  DATA 0
+             t:  DATA 0",
+        )
+        .unwrap();
+        for threads in [1, 2, 8] {
+            let mut network = Network::new(&program, 5).threads(threads);
+            let packet = network.run(&mut Start(false));
+            assert_eq!(
+                packet,
+                Ok(Packet {
+                    addr: 5,
+                    payload: vec![7, 5]
+                })
+            );
+        }
+
+        let mut network = Network::new(&assemble("IN -> [0]\nHLT").unwrap(), 3);
+        assert_eq!(network.run(&mut Start(false)), Err(IntCodeErr::End));
+    }
+}
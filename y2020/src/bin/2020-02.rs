@@ -1,7 +1,6 @@
 use std::io;
 
-use aoc_shared::{fold_decimal_from, read_input};
-use regex::bytes::Regex;
+use aoc_shared::{read_input, scan};
 
 type Output = Vec<PasswordPolicy>;
 type Solved = i32;
@@ -15,18 +14,17 @@ struct PasswordPolicy {
 }
 
 fn parse_input(input: &[u8]) -> Output {
-    let mut ret = vec![];
-    let re =
-        Regex::new(r##"(?m)^(?<min>[[:digit:]]+)-(?<max>[[:digit:]]+) (?<chr>.): (?<pas>.*)$"##)
-            .unwrap();
-    re.captures_iter(input).for_each(|matcher| {
-        let min = fold_decimal_from(matcher.name("min").unwrap().as_bytes());
-        let max = fold_decimal_from(matcher.name("max").unwrap().as_bytes());
-        let chr = matcher.name("chr").unwrap().as_bytes()[0];
-        let pas = matcher.name("pas").unwrap().as_bytes().to_owned();
-        ret.push(PasswordPolicy { min, max, chr, pas });
-    });
-    ret
+    scan!("{}-{} {}: {}", lines input => (min: i32, max: i32, chr: char, pas: &[u8]))
+        .map(|policy| {
+            let (min, max, chr, pas) = policy.unwrap_or_else(|err| panic!("{err}"));
+            PasswordPolicy {
+                min,
+                max,
+                chr: chr as u8,
+                pas: pas.to_owned(),
+            }
+        })
+        .collect()
 }
 
 fn part1_sol(policy: &Output) -> Solved {
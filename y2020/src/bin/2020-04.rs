@@ -1,25 +1,42 @@
 use std::io;
 
 use aoc_shared::read_input_to_string;
-use regex::RegexSet;
 
 type Output<'a> = Vec<Vec<&'a str>>;
 type Solved = i64;
 
-const P1_RE: &[&str] = &[
-    r"^cid:", r"^byr:", r"^iyr:", r"^eyr:", r"^hcl:", r"^ecl:", r"^pid:", r"^hgt:",
-];
-
-const P2_RE: &[&str] = &[
-    r"^cid:.+$",
-    r"^byr:(19[2-9][0-9]|200[0-2])$",
-    r"^iyr:(201[0-9]|2020)$",
-    r"^eyr:(202[0-9]|2030)$",
-    r"^hcl:#[0-9a-fA-F]{6}$",
-    r"^ecl:(amb|blu|brn|gry|grn|hzl|oth)$",
-    r"^pid:[0-9]{9}$",
-    r"^hgt:((1[5-8][0-9]|19[0-3])cm|(59|6[0-9]|7[0-6])in)$",
-];
+const FIELDS: [&str; 8] = ["cid", "byr", "iyr", "eyr", "hcl", "ecl", "pid", "hgt"];
+
+/// Exactly `len` digits, between `lo` and `hi` inclusive.
+fn num_in(digits: &str, len: usize, lo: u32, hi: u32) -> bool {
+    digits.len() == len
+        && digits.bytes().all(|c| c.is_ascii_digit())
+        && digits.parse().is_ok_and(|num| (lo..=hi).contains(&num))
+}
+
+fn valid_p1(_key: &str, _value: &str) -> bool {
+    true
+}
+
+fn valid_p2(key: &str, value: &str) -> bool {
+    match key {
+        "cid" => !value.is_empty(),
+        "byr" => num_in(value, 4, 1920, 2002),
+        "iyr" => num_in(value, 4, 2010, 2020),
+        "eyr" => num_in(value, 4, 2020, 2030),
+        "hcl" => value
+            .strip_prefix('#')
+            .is_some_and(|hex| hex.len() == 6 && hex.bytes().all(|c| c.is_ascii_hexdigit())),
+        "ecl" => matches!(value, "amb" | "blu" | "brn" | "gry" | "grn" | "hzl" | "oth"),
+        "pid" => num_in(value, 9, 0, 999_999_999),
+        "hgt" => match (value.strip_suffix("cm"), value.strip_suffix("in")) {
+            (Some(cm), _) => num_in(cm, 3, 150, 193),
+            (_, Some(inch)) => num_in(inch, 2, 59, 76),
+            _ => false,
+        },
+        _ => false,
+    }
+}
 
 fn parse_input(input: &'_ str) -> Output<'_> {
     input
@@ -28,18 +45,21 @@ fn parse_input(input: &'_ str) -> Output<'_> {
         .collect::<Vec<_>>()
 }
 
-fn solve(passports: &Output, re_set: &[&str]) -> Solved {
+fn solve(passports: &Output, valid: fn(&str, &str) -> bool) -> Solved {
     let mut tot_valid = 0;
-    let re = RegexSet::new(re_set).unwrap();
     'nextpass: for passport in passports {
-        let mut valid_entries = vec![false; re_set.len()];
+        let mut valid_entries = vec![false; FIELDS.len()];
         for &line in passport {
-            let matches = re.matches(line).into_iter().collect::<Vec<usize>>();
-            if matches.is_empty() {
-                continue 'nextpass;
+            let field = line.split_once(':').and_then(|(key, value)| {
+                FIELDS
+                    .iter()
+                    .position(|&field| field == key)
+                    .filter(|_| valid(key, value))
+            });
+            match field {
+                Some(field) => valid_entries[field] = true,
+                None => continue 'nextpass,
             }
-            assert_eq!(matches.len(), 1);
-            valid_entries[matches[0]] = true;
         }
 
         let has_cid = valid_entries[0];
@@ -54,8 +74,8 @@ fn solve(passports: &Output, re_set: &[&str]) -> Solved {
 fn main() -> io::Result<()> {
     let input = read_input_to_string()?;
     let parsed_input = parse_input(&input);
-    let part1 = solve(&parsed_input, P1_RE);
-    let part2 = solve(&parsed_input, P2_RE);
+    let part1 = solve(&parsed_input, valid_p1);
+    let part2 = solve(&parsed_input, valid_p2);
     print!("Part1: {part1}, ");
     print!("Part2: {part2}");
     println!();
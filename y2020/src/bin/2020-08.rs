@@ -1,4 +1,4 @@
-use aoc_shared::read_input_to_string;
+use aoc_shared::{find_cycle_by_key, read_input_to_string, CycleStrategy};
 use itertools::Itertools;
 use std::io;
 
@@ -82,51 +82,28 @@ fn parse_input(input: &str) -> Output {
         .collect::<Vec<Instruction>>()
 }
 
-/// basic Floyd cycle detector. the program counter is used to determine cycle.
-fn floyd_cycle(input: &Output) -> Result<(i32, i32), i32> {
-    let mut tortoise = Console::new();
-    let mut hare = Console::new();
-
-    loop {
-        tortoise = tortoise.step(input)?;
-        hare = hare.step(input)?.step(input)?;
-        if tortoise.pc == hare.pc {
-            break;
-        }
-    }
-
-    let mut mu = 0;
-    tortoise = Console::new();
-    loop {
-        tortoise = tortoise.step(input)?;
-        hare = hare.step(input)?;
-        mu += 1;
-        if tortoise.pc == hare.pc {
-            break;
-        }
-    }
-
-    let mut lambda = 0;
-    hare = tortoise.step(input)?;
-    loop {
-        hare = hare.step(input)?;
-        lambda += 1;
-        if tortoise.pc == hare.pc {
-            break;
-        }
-    }
+fn run_for(input: &Output, steps: usize) -> Result<Console, i32> {
+    (0..steps).try_fold(Console::new(), |console, _| console.step(input))
+}
 
-    Ok((mu, lambda))
+/// Floyd cycle detection, the program counter is used to determine cycle.
+/// A terminated program is a cycle of its final state, which is reported as the Err(accumulator).
+fn floyd_cycle(input: &Output) -> Result<(usize, usize), i32> {
+    let (mu, lambda) = find_cycle_by_key(
+        &Ok(Console::new()),
+        |console| console.and_then(|c| c.step(input)),
+        |console| console.map(|c| c.pc),
+        CycleStrategy::Floyd,
+    );
+    run_for(input, mu).map(|_| (mu, lambda))
 }
 
 fn part1_sol(input: &Output) -> Solved {
     let (mu, lambda) = floyd_cycle(input).expect("There should be a cycle in this program.");
     // rerun a new program up to the first cycling instruction.
-    let mut acc = Console::new();
-    for _ in 0..(mu + lambda) {
-        acc = acc.step(input).expect("to not terminate");
-    }
-    acc.accumulate
+    run_for(input, mu + lambda)
+        .expect("to not terminate")
+        .accumulate
 }
 
 fn part2_sol(input: Output) -> Solved {
@@ -1,6 +1,6 @@
 use std::{fmt::Debug, io, vec};
 
-use aoc_shared::{read_input, AoCTokenizer, Token};
+use aoc_shared::{read_input, AoCTokenizer, CompactGraph, Token};
 
 #[derive(Debug, PartialEq, Copy, Clone)]
 pub enum CaveSize {
@@ -119,62 +119,32 @@ fn parse(input: Vec<u8>) -> Vec<GraphNode> {
             }
             Token::Space => (),
             Token::End => (),
+            Token::Number(_) | Token::Word(_) => (),
         }
     }
     nodes
 }
 
-fn solve(nodes: &[GraphNode], twice: Option<usize>) -> u64 {
-    let mut visit_map = vec![0; nodes.len()];
-    let mut stack = vec![(0usize, 0usize, &nodes[0])];
-    let mut paths = 0;
-
-    while let Some((idx, nidx, node)) = stack.pop() {
-        if node.ident == END {
-            //for (_, _, item) in &stack {
-            //    print!("{:?},", item);
-            //}
-            //println!("end");
-            paths += 1;
-            continue;
-        }
-
-        let mut i = nidx;
-        while i < node.nodes.len() {
-            let neigh = node.nodes[i];
-            if visit_map[neigh] < 1 {
-                break;
-            } else if let Some(t) = twice {
-                if t == neigh && visit_map[neigh] < 2 {
-                    break;
-                }
-            }
-            i += 1;
+fn solve(nodes: &[GraphNode], extra_visits: u32) -> u64 {
+    let mut graph = CompactGraph::new(nodes.len());
+    let mut large_caves = 0;
+    nodes.iter().enumerate().for_each(|(idx, node)| {
+        if node.cave == CaveSize::Large {
+            large_caves |= 1 << idx;
         }
-
-        if i < node.nodes.len() {
-            if node.cave == CaveSize::Small && nidx == 0 {
-                visit_map[idx] += 1;
-            }
-            stack.push((idx, i + 1, node));
-            let neigh_node = node.nodes[i];
-            stack.push((neigh_node, 0usize, &nodes[neigh_node]));
-        } else if node.cave == CaveSize::Small {
-            visit_map[idx] -= 1;
-        }
-    }
-    paths
+        node.nodes
+            .iter()
+            .for_each(|&neigh| graph.add_edge(idx, neigh, 1u32));
+    });
+    // start is always index 0, end is always index 1.
+    graph.count_paths(0, 1, large_caves, extra_visits)
 }
 
 pub fn main() -> io::Result<()> {
     let input = read_input()?;
     let root = parse(input);
-    let p1 = solve(&root, None);
-    let p2 = root
-        .iter()
-        .enumerate()
-        .filter(|(_, n)| n.cave == CaveSize::Small && n.ident != START && n.ident != END)
-        .fold(0u64, |acc, (idx, _)| (acc + solve(&root, Some(idx))) - p1);
-    println!("Part1 {}, Part2 {}", p1, p1 + p2);
+    let p1 = solve(&root, 0);
+    let p2 = solve(&root, 1);
+    println!("Part1 {}, Part2 {}", p1, p2);
     Ok(())
 }
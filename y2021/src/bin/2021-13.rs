@@ -49,6 +49,7 @@ fn parse(input: Vec<u8>) -> (Vec<Instruction>, Vec<(i32, i32)>) {
             }
             Token::Space => (),
             Token::End => (),
+            Token::Number(_) | Token::Word(_) => (),
         }
     }
 
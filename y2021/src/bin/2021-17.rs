@@ -1,6 +1,9 @@
 use std::{cmp::Ordering, collections::HashSet, io};
 
-use aoc_shared::{fold_decimal, read_input, AoCTokenizer, Token};
+use aoc_shared::{
+    parse::{expect, number, parse_all, preceded, Cursor, ParseResult, Parser},
+    read_input,
+};
 
 #[derive(Debug)]
 struct Points {
@@ -10,55 +13,25 @@ struct Points {
     ymin: i32,
 }
 
-enum Parse {
-    Nothing,
-    X,
-    Y,
+/// `lo..hi`, in either order.
+fn parse_range(c: &mut Cursor) -> ParseResult<(i32, i32)> {
+    let a: i32 = number().parse(c)?;
+    let b = preceded(expect(".."), number()).parse(c)?;
+    Ok((a.min(b), a.max(b)))
 }
 
 fn parse(input: Vec<u8>) -> Points {
-    let (point, _, _) = AoCTokenizer::new(&input).fold(
-        (
-            Points {
-                xmax: i32::MIN,
-                ymax: i32::MIN,
-                xmin: i32::MAX,
-                ymin: i32::MAX,
-            },
-            Parse::Nothing,
-            1,
-        ),
-        |(mut acc, state, neg), tok| match tok {
-            Token::Something(b"x") => (acc, Parse::X, 1),
-            Token::Something(b"y") => (acc, Parse::Y, 1),
-            Token::Delimiter(b'-') => (acc, state, -1),
-            Token::Something(num) if num != b"target" && num != b"area" => {
-                let n = if neg == -1 {
-                    neg * num.iter().fold(0i32, fold_decimal)
-                } else {
-                    num.iter().fold(0i32, fold_decimal)
-                };
-                if let Parse::X = state {
-                    if acc.xmax < n {
-                        acc.xmax = n;
-                    }
-                    if acc.xmin > n {
-                        acc.xmin = n
-                    }
-                } else if let Parse::Y = state {
-                    if acc.ymax < n {
-                        acc.ymax = n;
-                    }
-                    if acc.ymin > n {
-                        acc.ymin = n
-                    }
-                }
-                (acc, state, 1)
-            }
-            _ => (acc, state, 1),
-        },
-    );
-    point
+    let target = |c: &mut Cursor| {
+        let (xmin, xmax) = preceded(expect("target area: x="), parse_range).parse(c)?;
+        let (ymin, ymax) = preceded(expect(", y="), parse_range).parse(c)?;
+        Ok(Points {
+            xmax,
+            ymax,
+            xmin,
+            ymin,
+        })
+    };
+    parse_all(&input, target).unwrap_or_else(|err| panic!("{err}"))
 }
 
 // int math truncates too soon. also no sqrt()
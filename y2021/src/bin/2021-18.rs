@@ -333,14 +333,13 @@ fn parse(input: Vec<u8>) -> Vec<Snailnum> {
                 i += 1;
                 nodes.push(Snailnum::default());
             }
-            Token::End => {
+            Token::End
                 if nodes
                     .last()
                     .expect("Expected at least one Snailnum.")
-                    .is_empty()
-                {
-                    nodes.pop();
-                }
+                    .is_empty() =>
+            {
+                nodes.pop();
             }
             _ => (),
         }
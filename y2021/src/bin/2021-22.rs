@@ -1,4 +1,4 @@
-use aoc_shared::{fold_decimal_from, read_input, GroupTokenize, Token};
+use aoc_shared::{read_input, GroupTokens, IntervalBox, Token, Tokenize};
 use std::io;
 
 type Output = Vec<Cuboid>;
@@ -7,9 +7,7 @@ type Solved = i64;
 #[derive(Clone)]
 struct Cuboid {
     toggle: bool,
-    x: (i64, i64),
-    y: (i64, i64),
-    z: (i64, i64),
+    cube: IntervalBox<i64, 3>,
 }
 
 impl Cuboid {
@@ -17,57 +15,34 @@ impl Cuboid {
     // if the other is a negative, we make a positive slice out of self.
     // if positive, we negate the intersection from other.
     fn intersect(&self, other: &Self) -> Option<Cuboid> {
-        let toggle = !other.toggle;
-        let x0 = std::cmp::max(self.x.0, other.x.0);
-        let x1 = std::cmp::min(self.x.1, other.x.1);
-        let y0 = std::cmp::max(self.y.0, other.y.0);
-        let y1 = std::cmp::min(self.y.1, other.y.1);
-        let z0 = std::cmp::max(self.z.0, other.z.0);
-        let z1 = std::cmp::min(self.z.1, other.z.1);
-
-        if x0 > x1 || y0 > y1 || z0 > z1 {
-            None
-        } else {
-            Some(Cuboid {
-                toggle,
-                x: (x0, x1),
-                y: (y0, y1),
-                z: (z0, z1),
-            })
-        }
+        self.cube.intersect(&other.cube).map(|cube| Cuboid {
+            toggle: !other.toggle,
+            cube,
+        })
     }
 }
 
 fn parse_input(input: &[u8]) -> Output {
     input
+        .tokenize()
+        .with_numbers()
         .group_tokens(Token::Newline)
         .map(|toks| {
             let mut toks = toks.into_iter();
             let toggle = toks.next().expect("Line to have content");
-            let toggle = matches!(toggle, Token::Something(b"on"));
+            let toggle = matches!(toggle, Token::Word(b"on"));
             let mut vals: [(Option<i64>, Option<i64>); 3] =
                 [(None, None), (None, None), (None, None)];
             let mut vi = 4usize;
-            let mut sign = 1;
             toks.for_each(|tok| match tok {
-                Token::Something(w) if w[0].is_ascii_alphabetic() => vi = (w[0] - b'x') as usize,
-                Token::Something(w) => {
-                    if vals[vi].0.is_none() {
-                        vals[vi].0 = Some(sign * fold_decimal_from::<i64>(w));
-                        sign = 1;
-                    } else {
-                        vals[vi].1 = Some(sign * fold_decimal_from::<i64>(w));
-                        sign = 1;
-                    }
-                }
-                Token::Delimiter(b'-') => sign = -1,
+                Token::Word(w) => vi = (w[0] - b'x') as usize,
+                Token::Number(n) if vals[vi].0.is_none() => vals[vi].0 = Some(n),
+                Token::Number(n) => vals[vi].1 = Some(n),
                 _ => (),
             });
             Cuboid {
                 toggle,
-                x: (vals[0].0.unwrap(), vals[0].1.unwrap()),
-                y: (vals[1].0.unwrap(), vals[1].1.unwrap()),
-                z: (vals[2].0.unwrap(), vals[2].1.unwrap()),
+                cube: IntervalBox::from_inclusive(vals.map(|(lo, hi)| (lo.unwrap(), hi.unwrap()))),
             }
         })
         .collect::<Vec<_>>()
@@ -81,9 +56,10 @@ fn part1_sol(input: &Output) -> Solved {
     let filtered = input
         .iter()
         .flat_map(|c| {
-            if [c.x.0, c.x.1, c.y.0, c.y.1, c.z.0, c.z.1]
+            if c.cube
+                .0
                 .iter()
-                .all(p1_filter)
+                .all(|r| p1_filter(&r.start) && p1_filter(&(r.end - 1)))
             {
                 Some(c.clone())
             } else {
@@ -110,9 +86,9 @@ fn part2_sol(input: Output) -> Solved {
 
     divcubes
         .into_iter()
-        .map(|Cuboid { toggle, x, y, z }| {
+        .map(|Cuboid { toggle, cube }| {
             let sign = if toggle { 1 } else { -1 };
-            sign * (x.1 - x.0 + 1) * (y.1 - y.0 + 1) * (z.1 - z.0 + 1)
+            sign * cube.volume()
         })
         .sum()
 }
@@ -61,7 +61,7 @@ fn parse_input(input: Vec<u8>) -> Parsed {
                 Token::Something(_) | Token::Delimiter(_) | Token::Space => (acc, padding + 1),
                 Token::Newline => (acc, 0),
                 Token::End => panic!("End of the input came before fully parsing crate positions."),
-                Token::DoubleNewline => unreachable!(),
+                Token::DoubleNewline | Token::Number(_) | Token::Word(_) => unreachable!(),
             },
         );
 
@@ -89,8 +89,8 @@ fn main() -> io::Result<()> {
     let target = fs_used.abs_diff(FS_TARGET);
 
     let part2 = parsed_input
-        .iter()
-        .filter_map(|(_, sizeof)| {
+        .values()
+        .filter_map(|sizeof| {
             if target <= *sizeof {
                 Some(*sizeof)
             } else {
@@ -1,8 +1,8 @@
+use aoc_shared::{parse_decimal, read_input};
 use std::{
     fmt::{Display, Write},
     io,
 };
-use aoc_shared::{fold_decimal, read_input, AoCTokenizer, Token};
 
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
 enum Instruction {
@@ -36,41 +36,21 @@ impl Display for CrtStates {
 }
 
 fn parse_input(input: Vec<u8>) -> Vec<Instruction> {
-    let tokenizer = AoCTokenizer::new(&input);
-    tokenizer
-        .fold(
-            (Vec::new(), None, false),
-            |(mut acc, instruction, is_neg), token| match token {
-                Token::Something(word) if instruction.is_none() => match word {
-                    b"noop" => {
-                        acc.push(Instruction::Noop);
-                        (acc, None, false)
-                    }
-                    b"addx" => {
-                        acc.push(Instruction::Add);
-                        (acc, Some(Instruction::Add), false)
-                    }
-                    _ => {
-                        let word = word.escape_ascii();
-                        panic!("Invalid instruction: {word}")
-                    }
-                },
-                Token::Something(word) => {
-                    let num = word.iter().fold(0, fold_decimal) * if is_neg { -1 } else { 1 };
-                    if let Some(instr) = instruction {
-                        if instr == Instruction::Add {
-                            acc.push(Instruction::AddX(num));
-                        }
-                    }
-                    (acc, None, false)
+    input
+        .split(|&c| c == b'\n')
+        .filter(|line| !line.is_empty())
+        .flat_map(|line| match line {
+            b"noop" => vec![Instruction::Noop],
+            _ => match line.strip_prefix(b"addx ").map(parse_decimal) {
+                Some(Ok(num)) => vec![Instruction::Add, Instruction::AddX(num)],
+                Some(Err(err)) => panic!("Invalid addx operand: {err}"),
+                None => {
+                    let line = line.escape_ascii();
+                    panic!("Invalid instruction: {line}")
                 }
-                Token::Delimiter(delim) if instruction.is_some() => {
-                    (acc, instruction, delim == b'-')
-                }
-                _ => (acc, instruction, is_neg),
             },
-        )
-        .0
+        })
+        .collect()
 }
 
 fn solve(input: &[Instruction]) -> (i64, CrtStates) {
@@ -1,5 +1,11 @@
-use aoc_shared::{fold_decimal, read_input, RecordGrouper, Token};
-use num::traits::AsPrimitive;
+use aoc_shared::{
+    math::crt,
+    parse::{
+        expect, expect_word, line, many, number, one_of, optional, parse_all, preceded, record,
+        sep_by, terminated, Cursor, ParseResult, Parser,
+    },
+    read_input,
+};
 use std::io;
 
 type Output = Vec<MonkeyScript>;
@@ -49,152 +55,48 @@ struct MonkeyScript {
     div_and_branch: Op,
 }
 
-#[derive(PartialEq, Eq, Default)]
-enum Step {
-    #[default]
-    Uknk,
-    Item,
-    Oper,
-    Test,
-    TestCond,
-    TestCondT,
-    TestCondF,
-}
-
-#[derive(Default, Debug)]
-struct MonkeyPartial {
-    items: Vec<i64>,
-    oper: (Option<u8>, Option<i64>),
-    test: (Option<i64>, Option<i64>, Option<i64>),
-}
-
-impl From<MonkeyPartial> for MonkeyScript {
-    fn from(value: MonkeyPartial) -> Self {
-        let items = value.items;
-        let oper = value.oper;
-        let divver = value.test;
-        let operation: Op = if let (Some(op), val) = oper {
-            (op, val)
-        } else {
-            panic!("Incomplete monkey! missing an operator. {oper:?}");
+/// Parse one monkey, e.g.
+/// ```text
+/// Monkey 0:
+///   Starting items: 79, 98
+///   Operation: new = old * 19
+///   Test: divisible by 23
+///     If true: throw to monkey 2
+///     If false: throw to monkey 3
+/// ```
+fn parse_monkey(c: &mut Cursor) -> ParseResult<MonkeyScript> {
+    line(preceded(
+        expect("Monkey"),
+        terminated(number::<i64>(), expect(":")),
+    ))
+    .parse(c)?;
+    let items = line(preceded(
+        expect("Starting items:"),
+        sep_by(number(), expect(",")),
+    ))
+    .parse(c)?;
+    let op = preceded(expect("Operation: new = old"), one_of(b"*+")).parse(c)?;
+    // None means the operand is `old`.
+    let operand = line(|c: &mut Cursor| {
+        let operand = optional(number()).parse(c)?;
+        if operand.is_none() {
+            expect_word("old").parse(c)?;
         }
-        .into();
-        let div_and_branch = if let (Some(num), Some(monkey1), Some(monkey2)) = divver {
-            Op::Div(Some(num), monkey1, monkey2)
-        } else {
-            panic!("Incomplete monkey! Missing a divisible number, monkey_true or monkey_false. {divver:?}");
-        };
-
-        MonkeyScript {
-            items,
-            operation,
-            div_and_branch,
-        }
-    }
-}
-
-fn is_num_and_parse<T>(input: &[u8]) -> Option<T>
-where
-    T: Copy + 'static,
-    T: std::ops::Add<Output = T>,
-    T: std::ops::Mul<Output = T>,
-    u8: num::traits::AsPrimitive<T>,
-{
-    match input[0] {
-        b'0'..=b'9' => Some(input.iter().fold(0.as_(), fold_decimal)),
-        _ => None,
-    }
+        Ok(operand)
+    })
+    .parse(c)?;
+    let test = line(preceded(expect("Test: divisible by"), number())).parse(c)?;
+    let if_true = line(preceded(expect("If true: throw to monkey"), number())).parse(c)?;
+    let if_false = line(preceded(expect("If false: throw to monkey"), number())).parse(c)?;
+    Ok(MonkeyScript {
+        items,
+        operation: (op, operand).into(),
+        div_and_branch: Op::Div(Some(test), if_true, if_false),
+    })
 }
 
 fn parse_input(input: Vec<u8>) -> Output {
-    let tokenizer = RecordGrouper::new(&input);
-    tokenizer
-        .map(|token_grp| {
-            token_grp
-                .iter()
-                .fold(
-                    (MonkeyPartial::default(), Step::Uknk),
-                    |(mut monkey, step), token| match token {
-                        Token::Something(word) => match step {
-                            Step::Item => {
-                                let num = word.iter().fold(0i64, fold_decimal);
-                                monkey.items.push(num);
-                                (monkey, step)
-                            }
-                            Step::Oper => {
-                                monkey.oper.1 = is_num_and_parse(word);
-                                (monkey, step)
-                            }
-                            Step::Test => {
-                                monkey.test.0 = is_num_and_parse(word);
-                                (monkey, step)
-                            }
-                            Step::TestCond => match *word {
-                                b"true" => (monkey, Step::TestCondT),
-                                b"false" => (monkey, Step::TestCondF),
-                                _ => (monkey, step),
-                            },
-                            Step::TestCondT => {
-                                monkey.test.1 = is_num_and_parse(word);
-                                (monkey, step)
-                            }
-                            Step::TestCondF => {
-                                monkey.test.2 = is_num_and_parse(word);
-                                (monkey, step)
-                            }
-                            Step::Uknk => match *word {
-                                b"items" => (monkey, Step::Item),
-                                b"Operation" => (monkey, Step::Oper),
-                                b"Test" => (monkey, Step::Test),
-                                b"If" => (monkey, Step::TestCond),
-                                _ => (monkey, step),
-                            },
-                        },
-                        Token::Delimiter(delim) if step == Step::Oper => match *delim {
-                            b'*' | b'+' => {
-                                monkey.oper.0 = Some(*delim);
-                                (monkey, step)
-                            }
-                            _ => (monkey, step),
-                        },
-                        Token::Newline => (monkey, Step::Uknk),
-                        _ => (monkey, step),
-                    },
-                )
-                .0
-                .into()
-        })
-        .collect::<Vec<MonkeyScript>>()
-}
-
-fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
-    if a == 0 {
-        (b, 0, 1)
-    } else {
-        let (g, x, y) = extended_gcd(b % a, a);
-        (g, y - (b / a) * x, x)
-    }
-}
-
-fn inverse_mod(a: i64, b: i64) -> Option<i64> {
-    let (g, x, _) = extended_gcd(a, b);
-    if g == 1 {
-        Some((x % b + b) % b)
-    } else {
-        None
-    }
-}
-
-fn chinese_rem(num: i64, moduli: &[i64]) -> Option<i64> {
-    let prod = moduli.iter().product::<i64>();
-    moduli
-        .iter()
-        .try_fold(0i64, |acc, &m| {
-            let prod = prod / m;
-            let rem = num % m;
-            Some(acc + (rem * inverse_mod(prod, m)? * prod))
-        })
-        .map(|sum| sum % prod)
+    parse_all(&input, many(record(parse_monkey))).unwrap_or_else(|err| panic!("{err}"))
 }
 
 fn solve_p1(mut monkeys: Vec<MonkeyScript>) -> i64 {
@@ -252,7 +154,9 @@ fn solve_p2(mut monkeys: Vec<MonkeyScript>) -> i64 {
                     let new_item = operator.do_op(item);
                     (
                         test.do_op(new_item) as usize,
-                        chinese_rem(new_item, &mods).expect("monkey divisors are not coprime."),
+                        crt(mods.iter().map(|&m| (new_item % m, m)))
+                            .expect("monkey divisors are consistent.")
+                            .0,
                     )
                 })
                 .collect::<Vec<(usize, i64)>>();
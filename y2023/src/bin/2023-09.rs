@@ -1,4 +1,4 @@
-use aoc_shared::{atoi, read_input, Token, Tokenize};
+use aoc_shared::{read_input, Token, Tokenize};
 use itertools::Itertools;
 use std::io;
 
@@ -7,21 +7,18 @@ type Output = Vec<Vec<i64>>;
 fn parse_input(input: &[u8]) -> Output {
     input
         .tokenize()
-        .fold(
-            (vec![], vec![], 1),
-            |(mut acc, mut line, is_neg), tok| match tok {
-                Token::Something(num) => {
-                    line.push(is_neg * atoi::<i64, 10>(num));
-                    (acc, line, 1)
-                }
-                Token::Delimiter(b'-') => (acc, line, -1),
-                Token::Newline | Token::DoubleNewline | Token::End if !line.is_empty() => {
-                    acc.push(line);
-                    (acc, vec![], 1)
-                }
-                _ => (acc, line, 1),
-            },
-        )
+        .with_numbers()
+        .fold((vec![], vec![]), |(mut acc, mut line), tok| match tok {
+            Token::Number(num) => {
+                line.push(num);
+                (acc, line)
+            }
+            Token::Newline | Token::DoubleNewline | Token::End if !line.is_empty() => {
+                acc.push(line);
+                (acc, vec![])
+            }
+            _ => (acc, line),
+        })
         .0
 }
 
@@ -1,5 +1,5 @@
-use aoc_shared::{advanced_cli, parse_to_flat2d, FlatVec2D, Rot2D};
-use std::{collections::HashMap, io};
+use aoc_shared::{advanced_cli, nth_state, parse_to_flat2d, FlatVec2D, Rot2D};
+use std::io;
 
 type Output = FlatVec2D<Rocks>;
 
@@ -43,49 +43,51 @@ fn calc_load(grid: &Output) -> usize {
     ret
 }
 
-fn solve2(grid: &Output, cycle_cnt: usize, is_p1: bool) -> usize {
-    let mut cycle_detector = HashMap::new();
-    let mut g2 = grid.clone();
-    let mut i = 0;
-    'out: while i < cycle_cnt {
-        for (cardinal, xend, yend) in [
-            (Rot2D::None, grid.1, grid.2),
-            (Rot2D::Clock270, grid.2, grid.1),
-            (Rot2D::Clock180, grid.1, grid.2),
-            (Rot2D::Clock90, grid.2, grid.1),
-        ] {
-            // from left to right
-            for x in 0..xend {
-                let mut last = 0;
-                // go north to south
-                for y in 0..yend {
-                    let t = g2[(x, y, cardinal)];
-                    use Rocks::*;
-                    match t {
-                        Square => last = y + 1,
-                        Rounded => {
-                            if last != y {
-                                g2[(x, last, cardinal)] = Rounded;
-                                g2[(x, y, cardinal)] = Empty;
-                            }
-                            last += 1;
-                        }
-                        _ => (),
+fn tilt(g2: &mut Output, (cardinal, xend, yend): (Rot2D, usize, usize)) {
+    // from left to right
+    for x in 0..xend {
+        let mut last = 0;
+        // go north to south
+        for y in 0..yend {
+            let t = g2[(x, y, cardinal)];
+            use Rocks::*;
+            match t {
+                Square => last = y + 1,
+                Rounded => {
+                    if last != y {
+                        g2[(x, last, cardinal)] = Rounded;
+                        g2[(x, y, cardinal)] = Empty;
                     }
+                    last += 1;
                 }
-            }
-            if is_p1 {
-                break 'out;
+                _ => (),
             }
         }
-        i += 1;
-        // we skip over common periods...
-        // insert returns the old result if it existed.
-        if let Some(cycle_amt) = cycle_detector.insert(g2.0.clone(), i) {
-            let period = i - cycle_amt;
-            i += (cycle_cnt - i) / period * period;
-        }
     }
+}
+
+fn spin_cycle(grid: &Output) -> Output {
+    let mut g2 = grid.clone();
+    [
+        (Rot2D::None, grid.1, grid.2),
+        (Rot2D::Clock270, grid.2, grid.1),
+        (Rot2D::Clock180, grid.1, grid.2),
+        (Rot2D::Clock90, grid.2, grid.1),
+    ]
+    .into_iter()
+    .for_each(|tilt_dir| tilt(&mut g2, tilt_dir));
+    g2
+}
+
+fn solve2(grid: &Output, cycle_cnt: usize, is_p1: bool) -> usize {
+    let g2 = if is_p1 {
+        let mut g2 = grid.clone();
+        tilt(&mut g2, (Rot2D::None, grid.1, grid.2));
+        g2
+    } else {
+        // we skip over common periods...
+        nth_state(grid, spin_cycle, cycle_cnt)
+    };
     #[cfg(debug_assertions)]
     {
         println!("{g2:?}");
@@ -1,29 +1,25 @@
-use aoc_shared::{parse_to_flat2d, read_input, FlatVec2D};
+use aoc_shared::{parse_to_flat2d, read_input, Dir4, FlatVec2D, Point2};
 use std::{
     collections::{BinaryHeap, HashMap},
     io,
 };
 
-type Pxy = (usize, usize);
-type Pixy = (isize, isize);
-type Vxy = (i8, i8);
-type Key = (Pxy, Pxy, Vxy);
+/// (position, where the current straight line started, direction)
+type Key = (Point2<usize>, Point2<usize>, Dir4);
 type DistMap = HashMap<Key, u64>;
 
-fn get_neigh(x: isize, y: isize, lx: isize, ly: isize, ix: i8, iy: i8) -> [(Pixy, Pixy, Vxy); 3] {
-    match (ix, iy) {
-        (xdir, 0) => [
-            ((x, y - 1), (x, y - 1), (0, -1)),
-            ((x + xdir as isize, y), (lx, ly), (ix, iy)),
-            ((x, y + 1), (x, y + 1), (0, 1)),
-        ],
-        (0, ydir) => [
-            ((x - 1, y), (x - 1, y), (-1, 0)),
-            ((x, y + ydir as isize), (lx, ly), (ix, iy)),
-            ((x + 1, y), (x + 1, y), (1, 0)),
-        ],
-        _ => unreachable!(),
-    }
+/// Turn left, go straight or turn right; turning starts a new line.
+fn get_neigh(nodes: &FlatVec2D<u8>, (pos, line_start, dir): Key) -> impl Iterator<Item = Key> + '_ {
+    [
+        (dir.turn_left(), true),
+        (dir, false),
+        (dir.turn_right(), true),
+    ]
+    .into_iter()
+    .filter_map(move |(ndir, turned)| {
+        let next = nodes.step(pos, ndir)?;
+        Some((next, if turned { next } else { line_start }, ndir))
+    })
 }
 
 /// We need a custom Ord & PartialOrd to make BinaryHeap a minheap.
@@ -80,63 +76,48 @@ fn dist_cmp(ld: usize, old: usize, turned: bool, is_ultra: bool) -> bool {
 
 fn solve(
     nodes: &FlatVec2D<u8>,
-    start: (usize, usize),
-    end: (usize, usize),
+    start: Point2<usize>,
+    end: Point2<usize>,
     ultra_crucible: bool,
 ) -> Option<u64> {
     let mut dist: DistMap = HashMap::new();
     let mut heap = BinaryHeap::new();
-    dist.insert((start, start, (1i8, 0i8)), 0u64);
-    dist.insert((start, start, (0i8, 1i8)), 0u64);
-
-    heap.push(HeapState {
-        state: (start, start, (1, 0)),
-        cost: 0, // we ignore cost of starting in this node
-    });
-    heap.push(HeapState {
-        state: (start, start, (0, 1)),
-        cost: 0, // we ignore cost of starting in this node
-    });
+    for dir in [Dir4::East, Dir4::South] {
+        dist.insert((start, start, dir), 0u64);
+        heap.push(HeapState {
+            state: (start, start, dir),
+            cost: 0, // we ignore cost of starting in this node
+        });
+    }
 
-    while let Some(HeapState {
-        state: ((x, y), (lx, ly), (ix, iy)),
-        cost,
-    }) = heap.pop()
-    {
-        if (x, y) == end {
+    while let Some(HeapState { state, cost }) = heap.pop() {
+        let (pos, line_start, _) = state;
+        if pos == end {
             return Some(cost);
-        } else if cost > get_dist(&dist, &((x, y), (lx, ly), (ix, iy))) {
+        } else if cost > get_dist(&dist, &state) {
             continue;
         }
 
-        get_neigh(x as isize, y as isize, lx as isize, ly as isize, ix, iy)
-            .iter()
-            .filter(|((x1, y1), _, _)| nodes.in_bounds(*x1, *y1))
-            .for_each(|&((x1, y1), (lx1, ly1), (ix1, iy1))| {
-                let x1 = x1 as usize;
-                let y1 = y1 as usize;
-                let lx1 = lx1 as usize;
-                let ly1 = ly1 as usize;
-
-                let line_dist = x1.abs_diff(lx1).max(y1.abs_diff(ly1));
-                let oline_dist = x.abs_diff(lx).max(y.abs_diff(ly));
-                let turned = !(lx == lx1 && ly == ly1);
-
-                let neigh_cost = (nodes[(x1, y1)] - b'0') as u64;
-                let new_cost = if dist_cmp(line_dist, oline_dist, turned, ultra_crucible) {
-                    cost + neigh_cost
-                } else {
-                    u64::MAX
-                };
-
-                let key = ((x1, y1), (lx1, ly1), (ix1, iy1));
-                if cmp_and_swap_dist(&mut dist, key, new_cost) {
-                    heap.push(HeapState {
-                        state: key,
-                        cost: new_cost,
-                    });
-                }
-            });
+        get_neigh(nodes, state).for_each(|key| {
+            let (next, nline_start, _) = key;
+            let line_dist = next.chebyshev(nline_start);
+            let oline_dist = pos.chebyshev(line_start);
+            let turned = line_start != nline_start;
+
+            let neigh_cost = (nodes[next] - b'0') as u64;
+            let new_cost = if dist_cmp(line_dist, oline_dist, turned, ultra_crucible) {
+                cost + neigh_cost
+            } else {
+                u64::MAX
+            };
+
+            if cmp_and_swap_dist(&mut dist, key, new_cost) {
+                heap.push(HeapState {
+                    state: key,
+                    cost: new_cost,
+                });
+            }
+        });
     }
     None
 }
@@ -144,10 +125,9 @@ fn solve(
 fn main() -> io::Result<()> {
     let input = read_input()?;
     let grid = parse_to_flat2d(&input);
-    let part1 =
-        solve(&grid, (0, 0), (grid.1 - 1, grid.2 - 1), false).expect("expected to find min path.");
-    let part2 =
-        solve(&grid, (0, 0), (grid.1 - 1, grid.2 - 1), true).expect("expected to find min path.");
+    let end = Point2::new(grid.1 - 1, grid.2 - 1);
+    let part1 = solve(&grid, Point2::default(), end, false).expect("expected to find min path.");
+    let part2 = solve(&grid, Point2::default(), end, true).expect("expected to find min path.");
     print!("Part1: {part1}, ");
     print!("Part2: {part2}");
     println!();
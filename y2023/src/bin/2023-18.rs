@@ -1,9 +1,7 @@
-use aoc_shared::{atoi, destructure_or_none, read_input, Token, Tokenize};
+use aoc_shared::{atoi, destructure_or_none, geom::lattice_points, read_input, Token, Tokenize};
 use itertools::Itertools;
 use std::io;
 
-const ARBITRARY_START: (i64, i64) = (100_000, 100_000);
-
 type Instructions = Vec<(u8, i64)>;
 
 fn parse1(input: &[u8]) -> Instructions {
@@ -37,31 +35,22 @@ fn parse2(input: &[u8]) -> Instructions {
 }
 
 fn solve(instructions: Instructions) -> i64 {
-    let (points, perimeter) = instructions.into_iter().fold(
-        (vec![ARBITRARY_START], 0i64),
-        |(mut acc, perim), (dir, amt)| {
-            let &(x, y) = acc.last().unwrap();
-            let next = match dir {
-                b'U' => (x, y - amt),
-                b'D' => (x, y + amt),
-                b'L' => (x - amt, y),
-                b'R' => (x + amt, y),
+    let points = instructions
+        .into_iter()
+        .scan((0i64, 0i64), |(x, y), (dir, amt)| {
+            match dir {
+                b'U' => *y -= amt,
+                b'D' => *y += amt,
+                b'L' => *x -= amt,
+                b'R' => *x += amt,
                 _ => panic!("Invalid direction given: {:?}", dir),
             };
-            acc.push(next);
-            (acc, amt + perim)
-        },
-    );
-    assert_eq!(points[0], *points.last().unwrap());
-    (points
-        .into_iter()
-        .tuple_windows()
-        .map(|((x1, y1), (x2, y2))| x1 * y2 - x2 * y1)
-        .sum::<i64>()
-        .abs()
-        + perimeter) // unlike day 10, perimeter IS a part of the area.
-        / 2
-        + 1
+            Some((*x, *y))
+        })
+        .collect_vec();
+    assert_eq!(points.last(), Some(&(0, 0)));
+    // unlike day 10, the trench IS a part of the area.
+    lattice_points(&points)
 }
 
 fn main() -> io::Result<()> {
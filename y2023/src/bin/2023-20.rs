@@ -1,4 +1,4 @@
-use aoc_shared::read_input;
+use aoc_shared::{math::lcm_all, read_input};
 use nom::{
     branch::alt,
     bytes::complete::{tag, take_till, take_while1},
@@ -234,7 +234,7 @@ fn solve2(mut input: DesertMachine, inverse: InverseMap) -> u64 {
                 }
             });
     }
-    rx_s.into_iter().product()
+    lcm_all(rx_s)
 }
 
 fn main() -> io::Result<()> {
@@ -1,52 +1,20 @@
-use aoc_shared::{parse_to_flat2d, read_input, FlatVec2D, Neighbor};
+use aoc_shared::{compress_grid_directed, parse_to_flat2d, read_input, CompactGraph, FlatVec2D};
 use std::io;
 
 type XY = (usize, usize);
 
-#[derive(Debug)]
-enum DfsNode {
-    Explore { at: XY },
-    BackTrack { at: XY, unexplored: Vec<DfsNode> },
-}
-
 fn solve(nodes: &FlatVec2D<MazeCell>, start: XY, end: XY, is_part2: bool) -> usize {
-    use DfsNode::*;
-    let mut ret = 0;
-    let mut stack = vec![Explore { at: start }];
-    let mut curr_track = FlatVec2D::<bool>::new(nodes.1, nodes.2);
-    while let Some(mut dfs) = stack.pop() {
-        match &mut dfs {
-            Explore { at: (x, y) } => {
-                if (*x, *y) == end {
-                    if ret < stack.len() {
-                        ret = stack.len();
-                    }
-                    continue;
-                } else if curr_track[(*x, *y)] {
-                    continue;
-                }
-                curr_track[(*x, *y)] = true;
-                let unexplored = nodes
-                    .get_neigh_card_iter((*x, *y))
-                    .filter(|Neighbor(n, nx, ny)| n.can_traverse(*nx, *ny, *x, *y, is_part2))
-                    .map(|Neighbor(_, nx, ny)| Explore { at: (nx, ny) })
-                    .collect::<Vec<DfsNode>>();
-                stack.push(BackTrack {
-                    unexplored,
-                    at: (*x, *y),
-                });
-            }
-            BackTrack { unexplored, at } => {
-                if let Some(n) = unexplored.pop() {
-                    stack.push(dfs);
-                    stack.push(n);
-                } else {
-                    curr_track[*at] = false;
-                }
-            }
-        }
-    }
-    ret
+    let graph = compress_grid_directed(
+        nodes,
+        &[start, end],
+        |cell| !matches!(cell, MazeCell::Forest),
+        |(lx, ly), (sx, sy)| nodes[(sx, sy)].can_traverse(sx, sy, lx, ly, is_part2),
+    );
+    let start = graph.node_id(start).expect("start to be a node");
+    let end = graph.node_id(end).expect("end to be a node");
+    CompactGraph::from(&graph)
+        .longest_path_par(start, end)
+        .expect("a path to the end")
 }
 
 #[derive(Default, Copy, Clone)]
@@ -1,6 +1,12 @@
-use aoc_shared::{read_input, try_atoi, Token, Tokenize};
+use aoc_shared::{
+    geom::{Intersection, Line2},
+    linalg::{solve_integer_system, Rational},
+    parse::{expect, line, many, number, parse_all, preceded, Cursor, ParseResult, Parser},
+    read_input,
+};
 use itertools::Itertools;
 use std::io;
+#[cfg(feature = "z3")]
 use z3::{ast::Int, Solver};
 
 #[derive(Debug)]
@@ -13,108 +19,106 @@ struct Line {
     vz: i64,
 }
 
-type XY = (f64, f64);
-
-const P1_SEG_START: f64 = 200000000000000f64;
-// const P1_SEG_START: f64 = 7f64;
-const P1_SEG_END: f64 = 400000000000000f64;
-// const P1_SEG_END: f64 = 21f64;
+const P1_AREA_START: i128 = 200000000000000;
+// const P1_AREA_START: i128 = 7;
+const P1_AREA_END: i128 = 400000000000000;
+// const P1_AREA_END: i128 = 21;
 
 impl Line {
-    fn p12d_segment(&self) -> (XY, XY) {
-        let xs = self.px as f64;
-        let xe = (self.px + self.vx * 1000) as f64;
-        let ys = self.py as f64;
-        let ye = (self.py + self.vy * 1000) as f64;
-
-        ((xs, ys), (xe, ye))
-    }
-
-    pub fn p12d_insersects(&self, other: &Self) -> Option<(f64, f64)> {
-        let ((x1, y1), (x2, y2)) = self.p12d_segment();
-        let ((x3, y3), (x4, y4)) = other.p12d_segment();
-
-        let s21x = x2 - x1;
-        let s21y = y2 - y1;
-        let s43x = x4 - x3;
-        let s43y = y4 - y3;
-
-        let denom = s21x * s43y - s43x * s21y;
-        // intersects.
-        if denom == 0f64 {
-            return None;
-        }
-
-        let s13x = x1 - x3;
-        let s13y = y1 - y3;
-
-        let sn = s21x * s13y - s21y * s13x;
-        if sn < 0f64 && denom > 0f64 {
-            return None;
-        }
-        let tn = s43x * s13y - s43y * s13x;
-        if tn < 0f64 && denom > 0f64 {
-            return None;
-        }
-
-        if (sn > denom) != (denom > 0f64) || (tn > denom) != (denom > 0f64) {
-            return None;
-        }
-        let t = tn / denom;
-        Some((x1 + (t * s21x), y1 + (t * s21y)))
+    /// Future path of the hailstone, ignoring the Z axis.
+    fn p12d_ray(&self) -> Line2 {
+        Line2::ray(
+            [self.px as i128, self.py as i128],
+            [self.vx as i128, self.vy as i128],
+        )
     }
 }
 
 type Output = Vec<Line>;
 
-enum Neg {
-    Num(i64),
-    IsNeg,
+/// `px, py, pz @ vx, vy, vz`
+fn parse_hail(c: &mut Cursor) -> ParseResult<Line> {
+    let [px, py, pz] = parse_xyz(c)?;
+    let [vx, vy, vz] = preceded(expect("@"), parse_xyz).parse(c)?;
+    Ok(Line {
+        px,
+        py,
+        pz,
+        vx,
+        vy,
+        vz,
+    })
+}
+
+fn parse_xyz(c: &mut Cursor) -> ParseResult<[i64; 3]> {
+    let x = number().parse(c)?;
+    let y = preceded(expect(","), number()).parse(c)?;
+    let z = preceded(expect(","), number()).parse(c)?;
+    Ok([x, y, z])
 }
 
 fn parse_input(input: &[u8]) -> Output {
-    input
-        .tokenize()
-        .flat_map(|t| {
-            if let Token::Something(x) = t {
-                try_atoi::<i64, 10>(x).map(Neg::Num)
-            } else if let Token::Delimiter(b'-') = t {
-                Some(Neg::IsNeg)
-            } else {
-                None
-            }
-        })
-        .fold((Vec::new(), 1), |(mut acc, is_neg), tok| match tok {
-            Neg::Num(x) => {
-                acc.push(x * is_neg);
-                (acc, 1)
-            }
-            Neg::IsNeg => (acc, -1),
-        })
-        .0
-        .chunks(6)
-        .map(|c| Line {
-            px: c[0],
-            py: c[1],
-            pz: c[2],
-            vx: c[3],
-            vy: c[4],
-            vz: c[5],
-        })
-        .collect::<Vec<_>>()
+    parse_all(input, many(line(parse_hail))).unwrap_or_else(|err| panic!("{err}"))
 }
 
 fn solve2d(i: &[Line]) -> usize {
+    let area = Rational::from(P1_AREA_START)..=Rational::from(P1_AREA_END);
     i.iter()
         .tuple_combinations()
-        .flat_map(|(l1, l2)| l1.p12d_insersects(l2))
-        .filter(|(x, y)| {
-            (P1_SEG_START..=P1_SEG_END).contains(x) && (P1_SEG_START..=P1_SEG_END).contains(y)
+        .filter(|(l1, l2)| match l1.p12d_ray().intersect(&l2.p12d_ray()) {
+            Intersection::Point { at: [x, y], .. } => area.contains(&x) && area.contains(&y),
+            _ => false,
         })
         .count()
 }
 
-fn solve3d(i: Vec<Line>) -> Option<u64> {
+fn cross([ax, ay, az]: [i128; 3], [bx, by, bz]: [i128; 3]) -> [i128; 3] {
+    [ay * bz - az * by, az * bx - ax * bz, ax * by - ay * bx]
+}
+
+impl Line {
+    fn pos(&self) -> [i128; 3] {
+        [self.px as i128, self.py as i128, self.pz as i128]
+    }
+
+    fn vel(&self) -> [i128; 3] {
+        [self.vx as i128, self.vy as i128, self.vz as i128]
+    }
+}
+
+// The rock (P, V) hits every hailstone (p, v), so (p - P) x (v - V) = 0.
+// Expanding the cross product leaves a P x V term common to every hailstone,
+// subtracting the equations of two hailstones (0 and i) cancels it, leaving a linear equation:
+//   P x (vi - v0) + (pi - p0) x V = pi x vi - p0 x v0
+fn rock_equations(h0: &Line, hi: &Line) -> ([[i128; 6]; 3], [i128; 3]) {
+    let (p0, v0, pi, vi) = (h0.pos(), h0.vel(), hi.pos(), hi.vel());
+    let [wx, wy, wz] = [vi[0] - v0[0], vi[1] - v0[1], vi[2] - v0[2]];
+    let [dx, dy, dz] = [pi[0] - p0[0], pi[1] - p0[1], pi[2] - p0[2]];
+    let rows = [
+        [0, wz, -wy, 0, -dz, dy],
+        [-wz, 0, wx, dz, 0, -dx],
+        [wy, -wx, 0, -dy, dx, 0],
+    ];
+    let c0 = cross(p0, v0);
+    let ci = cross(pi, vi);
+    (rows, [ci[0] - c0[0], ci[1] - c0[1], ci[2] - c0[2]])
+}
+
+#[cfg_attr(feature = "z3", allow(dead_code))]
+fn solve3d(i: Vec<Line>) -> Option<i64> {
+    // any two pairs of hailstones with independent equations pin down the rock.
+    i[1..].iter().tuple_combinations().find_map(|(h1, h2)| {
+        let (r1, b1) = rock_equations(&i[0], h1);
+        let (r2, b2) = rock_equations(&i[0], h2);
+        let rock = solve_integer_system(&[r1, r2].concat(), &[b1, b2].concat())?;
+        i64::try_from(rock[0] + rock[1] + rock[2]).ok()
+    })
+}
+
+/// Alternative solver used in place of `solve3d` when built with the `z3` feature: hands every
+/// hailstone to z3 as a constraint on the rock instead of solving the linear system directly.
+#[cfg(feature = "z3")]
+fn solve_z3(i: Vec<Line>) -> Option<u64> {
     let solver = Solver::new();
     let x = Int::fresh_const("x");
     let y = Int::fresh_const("y");
@@ -175,7 +179,11 @@ fn main() -> io::Result<()> {
     let parsed_input = parse_input(&input);
     let part1 = solve2d(&parsed_input);
     print!("Part1: {part1}, ");
-    let part2 = solve3d(parsed_input).expect("expected an intercept point.");
+    #[cfg(not(feature = "z3"))]
+    let part2 = solve3d(parsed_input);
+    #[cfg(feature = "z3")]
+    let part2 = solve_z3(parsed_input);
+    let part2 = part2.expect("expected an intercept point.");
     print!("Part2: {part2}");
     println!();
     Ok(())
@@ -1,33 +1,34 @@
 use std::io;
 
-use aoc_shared::{fold_decimal_from, read_input};
-use regex::bytes::{Captures, Regex};
+use aoc_shared::{fold_decimal_from, read_input, scan};
 
 type Int = u64;
 
+/// One to three digits.
+fn mul_operand(digits: &[u8]) -> Option<Int> {
+    ((1..=3).contains(&digits.len()) && digits.iter().all(u8::is_ascii_digit))
+        .then(|| fold_decimal_from(digits))
+}
+
 fn solve(input: &[u8]) -> (Int, Int) {
-    let extract_num =
-        |m: &Captures, name| fold_decimal_from::<Int>(m.name(name).unwrap().as_bytes());
-    let re = Regex::new(
-        r##"(?x)
-         (?<do>do\(\))
-        |(?<dont>don't\(\))
-        |(?:mul\((?<n1>[[:digit:]]{1,3}),(?<n2>[[:digit:]]{1,3})\))
-    "##,
-    )
-    .unwrap();
-    let (p1, p2, _) = re
-        .captures_iter(input)
-        .fold((0, 0, true), |(p1, p2, enabled), m| {
-            if m.name("do").is_some() {
-                (p1, p2, true)
-            } else if m.name("dont").is_some() {
-                (p1, p2, false)
-            } else {
-                let n = extract_num(&m, "n1") * extract_num(&m, "n2");
-                (p1 + n, p2 + n * enabled as Int, enabled)
+    let (mut p1, mut p2, mut enabled) = (0, 0, true);
+    for i in 0..input.len() {
+        let rest = &input[i..];
+        if rest.starts_with(b"do()") {
+            enabled = true;
+        } else if rest.starts_with(b"don't()") {
+            enabled = false;
+        } else if rest.starts_with(b"mul(") {
+            let Ok(((n1, n2), _)) = scan!("mul({},{})", prefix rest => (n1: &[u8], n2: &[u8]))
+            else {
+                continue;
+            };
+            if let (Some(n1), Some(n2)) = (mul_operand(n1), mul_operand(n2)) {
+                p1 += n1 * n2;
+                p2 += n1 * n2 * enabled as Int;
             }
-        });
+        }
+    }
     (p1, p2)
 }
 
@@ -4,6 +4,11 @@ use std::{io, mem};
 
 type Int = u64;
 type Output = Vec<u64>;
+// stone values stay small, only the number of stones explodes.
+#[cfg(not(feature = "bigint"))]
+type Count = u64;
+#[cfg(feature = "bigint")]
+type Count = aoc_shared::big::BigUint;
 
 fn parse_input(input: &str) -> Output {
     input
@@ -30,17 +35,17 @@ const P1_CYCLE: Int = 25;
 // we reuse the existing p1 cycle for p2
 const P2_CONT: Int = 75 - P1_CYCLE;
 
-fn solve(input: &Output) -> [Int; 2] {
+fn solve(input: &Output) -> [Count; 2] {
     let mut memo = FxHashMap::default();
     let mut memo2 = FxHashMap::default();
     input.iter().for_each(|&n| {
-        *memo.entry(n).or_default() += 1;
+        *memo.entry(n).or_insert_with(Count::default) += Count::from(1u8);
     });
 
     [P1_CYCLE, P2_CONT].map(|cycles| {
         for _ in 0..cycles {
             memo.drain().for_each(|(plu, counts)| {
-                let mut add_new = |plu| *memo2.entry(plu).or_default() += counts;
+                let mut add_new = |plu| *memo2.entry(plu).or_default() += &counts;
                 match rules(plu) {
                     Ok(new) => add_new(new),
                     Err(new) => new.into_iter().for_each(add_new),
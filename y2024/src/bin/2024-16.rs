@@ -1,4 +1,4 @@
-use aoc_shared::{parse_to_flat2d, read_input, Dijkstra, FlatVec2D, HeapState};
+use aoc_shared::{parse_to_flat2d, read_input, Dijkstra, Dir4, FlatVec2D, HeapState, Point2};
 use rustc_hash::{FxHashMap, FxHashSet};
 use std::{fmt::Write, io};
 
@@ -37,20 +37,9 @@ impl std::fmt::Debug for X {
     }
 }
 
-#[cfg(debug_assertions)]
-fn from_rot((dx, dy): (isize, isize)) -> char {
-    match (dx.signum(), dy.signum()) {
-        (0, -1) => '^',
-        (1, 0) => '>',
-        (0, 1) => 'v',
-        (-1, 0) => '<',
-        _ => unreachable!(),
-    }
-}
-
 type Int = isize;
 type Output = FlatVec2D<X>;
-type Key = (Int, Int, Int, Int);
+type Key = (Point2<usize>, Dir4);
 
 macro_rules! push_path {
     ($dij:ident, $pathmap:ident, $key:ident, $cost:ident, $oldkey:ident) => {
@@ -70,30 +59,25 @@ macro_rules! push_path {
 }
 
 fn part1_sol(map: &Output) -> (Int, Int) {
-    let (mut sx, mut sy) = (-1, -1);
-    let (mut ex, mut ey) = (-1, -1);
-    map.xyrange().for_each(|(x, y)| match map[(x, y)] {
-        X::Start => (sx, sy) = (x as isize, y as isize),
-        X::End => (ex, ey) = (x as isize, y as isize),
-        _ => (),
-    });
-    if (sx, sy) == (-1, -1) || (ex, ey) == (-1, -1) {
+    let start = map.points().find(|&p| matches!(map[p], X::Start));
+    let end = map.points().find(|&p| matches!(map[p], X::End));
+    let (Some(start), Some(_)) = (start, end) else {
         panic!("No start or end found.");
-    }
+    };
 
     let mut dij = Dijkstra::<Key, Int>::new();
     let mut p2_pathmap: std::collections::HashMap<Key, Vec<Key>, rustc_hash::FxBuildHasher> =
         FxHashMap::default();
     let mut visited = FxHashSet::default();
     let mut part1 = Int::MAX;
-    dij.push((sx, sy, 1, 0), 0); // reindeer start easterly
+    dij.push((start, Dir4::East), 0); // reindeer start easterly
     while let Some(HeapState { key, cost }) = dij.pop() {
-        let (x, y, dx, dy) = key;
+        let (pos, dir) = key;
         // so we don't waste time traversing further... see below comment.
         if cost > part1 {
             break;
         }
-        match map[(x, y)] {
+        match map[pos] {
             X::Wall => continue,
             X::End => {
                 // I don't think it's possible for inputs (and not for example)
@@ -106,13 +90,12 @@ fn part1_sol(map: &Output) -> (Int, Int) {
                 let mut stack = vec![key];
                 while let Some(key) = stack.pop() {
                     if let Some(keys) = p2_pathmap.get(&key) {
-                        keys.iter().for_each(|&(x, y, _dx, _dy)| {
+                        keys.iter().for_each(|&(pos, _dir)| {
                             #[cfg(debug_assertions)]
                             {
-                                debug_map[(x as usize, y as usize)] =
-                                    X::DebugPath(from_rot((_dx, _dy)));
+                                debug_map[pos] = X::DebugPath(_dir.arrow());
                             }
-                            visited.insert((x, y));
+                            visited.insert(pos);
                         });
                         stack.extend(keys);
                     }
@@ -122,14 +105,16 @@ fn part1_sol(map: &Output) -> (Int, Int) {
             }
             _ => (),
         }
-        let cwkey = (x, y, -dy, dx);
-        let ccwkey = (x, y, dy, -dx);
-        let stepkey = (x + dx, y + dy, dx, dy);
+        let cwkey = (pos, dir.turn_right());
+        let ccwkey = (pos, dir.turn_left());
         let rotcost = cost + 1000;
         let stepcost = cost + 1;
         push_path!(dij, p2_pathmap, cwkey, rotcost, key);
         push_path!(dij, p2_pathmap, ccwkey, rotcost, key);
-        push_path!(dij, p2_pathmap, stepkey, stepcost, key);
+        if let Some(next) = map.step(pos, dir) {
+            let stepkey = (next, dir);
+            push_path!(dij, p2_pathmap, stepkey, stepcost, key);
+        }
     }
     (part1, visited.len() as Int + 1)
 }
@@ -1,56 +1,28 @@
-use std::{cmp::Ordering, io};
+use std::io;
 
-use aoc_shared::read_input_to_string;
+use aoc_shared::{IntervalSet, read_input_to_string};
 
-fn parse_input(i: &str) -> (Vec<(u64, u64)>, Vec<u64>) {
+fn parse_input(i: &str) -> (IntervalSet<u64>, Vec<u64>) {
     let (ranges, ids) = i.split_once("\n\n").expect("Invalid input.");
-    let mut ranges = ranges
-        .split_ascii_whitespace()
-        .map(|line| {
-            let (start, end) = line.split_once('-').expect("Range");
-            let start = start.parse::<u64>().expect("valid number");
-            let end = end.parse::<u64>().expect("valid number");
-            (start, end)
-        })
-        .collect::<Vec<_>>();
-    ranges.sort_unstable();
-    // dedup_by starts from the start at the rhs, and the candidate on the lhs
-    ranges.dedup_by(|(cand_s, cand_e), (_curr_s, curr_e)| {
-        // because of sort order, cand_s being -le curr_e means its in the range.
-        match cand_s.cmp(&curr_e) {
-            Ordering::Less | Ordering::Equal => {
-                *curr_e = *cand_e.max(curr_e);
-                true
-            }
-            Ordering::Greater => false,
-        }
+    let mut fresh = IntervalSet::new();
+    ranges.split_ascii_whitespace().for_each(|line| {
+        let (start, end) = line.split_once('-').expect("Range");
+        let start = start.parse::<u64>().expect("valid number");
+        let end = end.parse::<u64>().expect("valid number");
+        fresh.insert_inclusive(start, end);
     });
-    let mut ids = ids
+    let ids = ids
         .split_ascii_whitespace()
         .map(|num| num.parse::<u64>().expect("Valid number."))
         .collect::<Vec<_>>();
-    ids.sort_unstable();
-    (ranges, ids)
+    (fresh, ids)
 }
 
 fn main() -> io::Result<()> {
     let input = read_input_to_string()?;
-    let (ranges, ids) = parse_input(input.trim());
-    let mut part1 = 0;
-    let mut ri = 0;
-    'out: for id in ids {
-        while let Some(&(s, e)) = ranges.get(ri) {
-            if id <= e {
-                part1 += u64::from(s <= id);
-                continue 'out;
-            } else {
-                ri += 1;
-            }
-        }
-        // no more ranges, moot to check further.
-        break 'out;
-    }
-    let part2 = ranges.iter().map(|(s, e)| e - s + 1).sum::<u64>();
+    let (fresh, ids) = parse_input(input.trim());
+    let part1 = ids.iter().filter(|id| fresh.contains(id)).count();
+    let part2 = fresh.total_len();
     println!("Part1 {part1}  Part2 {part2}");
     Ok(())
 }
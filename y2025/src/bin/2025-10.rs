@@ -1,6 +1,11 @@
 use std::io;
 
-use aoc_shared::{Dijkstra, HeapState, read_input_to_string};
+use aoc_shared::{
+    Dijkstra, HeapState,
+    ilp::{Cmp, Ilp},
+    read_input_to_string,
+};
+#[cfg(feature = "z3")]
 use z3::ast::Int;
 
 #[derive(Debug)]
@@ -63,7 +68,50 @@ fn solve(ms: &[Machine]) -> u64 {
     ms.iter().map(|m| least_btn_for_patt(m.ind, &m.btn)).sum()
 }
 
+fn least_btn_for_jolts(m: &Machine) -> u64 {
+    let mut ilp = Ilp::new();
+    // a button can never be pressed more than the smallest counter it increments.
+    let vars = m
+        .btn
+        .iter()
+        .map(|btn| {
+            let hi = m
+                .jlt
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| btn & (1 << i) > 0)
+                .map(|(_, &jolt)| jolt as i64)
+                .min();
+            ilp.add_var(0, hi)
+        })
+        .collect::<Vec<_>>();
+    m.jlt.iter().enumerate().for_each(|(i, &jolt)| {
+        let bit = 1 << i;
+        let presses = m
+            .btn
+            .iter()
+            .zip(&vars)
+            .filter(|(btn, _)| *btn & bit > 0)
+            .map(|(_, &var)| (var, 1))
+            .collect::<Vec<_>>();
+        ilp.add_constraint(&presses, Cmp::Eq, jolt as i64);
+    });
+    ilp.minimize(&vars.iter().map(|&var| (var, 1)).collect::<Vec<_>>());
+    match ilp.solve() {
+        Ok(sol) => sol.objective as u64,
+        Err(e) => panic!("{e}"),
+    }
+}
+
+#[cfg_attr(feature = "z3", allow(dead_code))]
 fn solve2(ms: &[Machine]) -> u64 {
+    ms.iter().map(least_btn_for_jolts).sum()
+}
+
+/// Alternative solver used in place of `solve2` when built with the `z3` feature: minimizes the
+/// presses of each machine with z3's optimizer instead of the branch and bound ILP.
+#[cfg(feature = "z3")]
+fn solve2_z3(ms: &[Machine]) -> u64 {
     let mut ans = 0;
     for m in ms {
         let optim = z3::Optimize::new();
@@ -115,7 +163,10 @@ fn main() -> io::Result<()> {
     let input = read_input_to_string()?;
     let input = parse_input(input.trim());
     let part1 = solve(&input);
+    #[cfg(not(feature = "z3"))]
     let part2 = solve2(&input);
+    #[cfg(feature = "z3")]
+    let part2 = solve2_z3(&input);
     println!("Part1 {part1}  Part2 {part2}");
     Ok(())
 }